@@ -21,40 +21,6 @@ export type Verifier = {
     "- Merkle path verification (circuit-specific)"
   ],
   "instructions": [
-    {
-      "name": "verifyLegacy",
-      "docs": [
-        "Legacy verification for backward compatibility during migration",
-        "Only accepts test mode with exact magic match"
-      ],
-      "discriminator": [
-        142,
-        224,
-        118,
-        27,
-        25,
-        214,
-        252,
-        50
-      ],
-      "accounts": [
-        {
-          "name": "auditLog"
-        }
-      ],
-      "args": [
-        {
-          "name": "proof",
-          "type": "bytes"
-        },
-        {
-          "name": "magic",
-          "type": {
-            "option": "bytes"
-          }
-        }
-      ]
-    },
     {
       "name": "verifyProof",
       "docs": [
@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+
+use crate::ed25519::ed25519_signed_messages;
+use crate::ErrorCode;
+
+/// Current attestation format; bump when fields change
+pub const ATTESTATION_VERSION: u8 = 1;
+
+/// Message a relayer signs to authorize one withdrawal.
+///
+/// Serialized with Borsh, version first. `program_id` and `pool` are the domain
+/// separator: the same relayer key signing for another deployment or another pool
+/// produces different bytes, so an attestation cannot be replayed there.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Attestation {
+    pub version: u8,
+    pub program_id: Pubkey,
+    /// Pool (vault) PDA the withdrawal is paid from
+    pub pool: Pubkey,
+    pub mint: Pubkey,
+    pub nullifier: [u8; 32],
+    pub root: [u8; 32],
+    /// Token account credited by the withdrawal
+    pub recipient: Pubkey,
+    /// Total paid out of the vault, including `fee`
+    pub amount: u64,
+    /// Part of `amount` paid to the submitting relayer
+    pub fee: u64,
+    /// Unix timestamp after which the attestation is rejected
+    pub expiry: u64,
+}
+
+impl Attestation {
+    /// Attestation for this program at the current version
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(pool: Pubkey, mint: Pubkey, nullifier: [u8; 32], root: [u8; 32], recipient: Pubkey, amount: u64, fee: u64, expiry: u64) -> Self {
+        Self {
+            version: ATTESTATION_VERSION,
+            program_id: crate::ID,
+            pool,
+            mint,
+            nullifier,
+            root,
+            recipient,
+            amount,
+            fee,
+            expiry,
+        }
+    }
+
+    /// Exact bytes a relayer signs
+    pub fn to_message(&self) -> Vec<u8> {
+        self.try_to_vec().expect("attestation serialization is infallible")
+    }
+
+    /// Decode signed bytes, rejecting attestation versions this program does not know
+    pub fn decode(message: &[u8]) -> Result<Self> {
+        require!(message.first() == Some(&ATTESTATION_VERSION), ErrorCode::UnsupportedAttestationVersion);
+        Self::try_from_slice(message).map_err(|_| ErrorCode::InvalidAttestation.into())
+    }
+}
+
+/// Reject any signature in the Ed25519SigVerify instructions over an unknown attestation version
+pub fn check_attestation_versions(instructions: &[Vec<u8>]) -> Result<()> {
+    for data in instructions {
+        for (_, message) in ed25519_signed_messages(data)? {
+            Attestation::decode(message)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+
+use crate::{Admin, ErrorCode};
+
+impl Admin {
+    /// Nominate `new` as the next authority; replaces any earlier proposal
+    pub fn propose_authority(&mut self, new: Pubkey) -> Result<()> {
+        require!(new != Pubkey::default(), ErrorCode::InvalidAuthority);
+        self.pending_authority = Some(new);
+        Ok(())
+    }
+
+    /// Hand control to `signer` if it is the pending authority; returns the previous authority
+    pub fn accept_authority(&mut self, signer: Pubkey) -> Result<Pubkey> {
+        require!(self.pending_authority == Some(signer), ErrorCode::Unauthorized);
+        let previous = self.authority;
+        self.authority = signer;
+        self.pending_authority = None;
+        Ok(previous)
+    }
+
+    /// Drop the pending proposal; returns the key that was pending
+    pub fn cancel_authority_transfer(&mut self) -> Result<Pubkey> {
+        self.pending_authority.take().ok_or_else(|| ErrorCode::NoPendingAuthority.into())
+    }
+}
@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+
+use crate::{ErrorCode, RelayerRecord};
+
+/// Default delay between `request_unbond` and `withdraw_bond`
+pub const DEFAULT_UNBONDING_PERIOD: u64 = 7 * 24 * 60 * 60;
+/// Shortest unbonding period `set_bond_params` accepts, so a bond always stays
+/// slashable for a while after the relayer leaves
+pub const MIN_UNBONDING_PERIOD: u64 = 24 * 60 * 60;
+
+/// Reject an unbonding period shorter than `MIN_UNBONDING_PERIOD`
+pub fn check_unbonding_period(unbonding_period: u64) -> Result<()> {
+    require!(unbonding_period >= MIN_UNBONDING_PERIOD, ErrorCode::InvalidUnbondingPeriod);
+    Ok(())
+}
+
+impl RelayerRecord {
+    /// Whether the relayer has asked to leave and is waiting out the unbonding period
+    pub fn is_unbonding(&self) -> bool {
+        self.unbond_ready_at != 0
+    }
+
+    /// Whether the bond is large enough for the relayer to be registered
+    pub fn is_bonded(&self, min_bond: u64) -> bool {
+        !self.is_unbonding() && self.bond > 0 && self.bond >= min_bond
+    }
+
+    /// Add `amount` deposited into the escrow; not allowed while unbonding
+    pub fn add_bond(&mut self, amount: u64) -> Result<()> {
+        require!(!self.is_unbonding(), ErrorCode::RelayerUnbonding);
+        self.bond = self.bond.checked_add(amount).ok_or(ErrorCode::InsufficientRelayerBond)?;
+        Ok(())
+    }
+
+    /// Take `amount` out of the bond. Slashing is still possible while unbonding, which
+    /// is what the unbonding period is for.
+    pub fn slash(&mut self, amount: u64) -> Result<()> {
+        require!(amount > 0 && amount <= self.bond, ErrorCode::InsufficientRelayerBond);
+        self.bond -= amount;
+        Ok(())
+    }
+
+    /// Start an unbonding period of `unbonding_period` seconds at unix time `now`. The
+    /// release time is fixed here, so later changes to the period do not move it.
+    pub fn request_unbond(&mut self, now: i64, unbonding_period: u64) -> Result<()> {
+        require!(!self.is_unbonding(), ErrorCode::RelayerUnbonding);
+        require!(self.bond > 0, ErrorCode::InsufficientRelayerBond);
+        // 0 means "not unbonding", so clamp a pre-epoch clock to 1
+        let now = u64::try_from(now).unwrap_or(0);
+        self.unbond_ready_at = now.saturating_add(unbonding_period).max(1);
+        Ok(())
+    }
+
+    /// Release the whole remaining bond once the release time set by `request_unbond`
+    /// has passed, returning the amount to pay out
+    pub fn release_bond(&mut self, now: i64) -> Result<u64> {
+        require!(self.is_unbonding(), ErrorCode::UnbondNotReady);
+        let now = u64::try_from(now).map_err(|_| ErrorCode::UnbondNotReady)?;
+        require!(now >= self.unbond_ready_at, ErrorCode::UnbondNotReady);
+
+        let amount = self.bond;
+        self.bond = 0;
+        self.unbond_ready_at = 0;
+        Ok(amount)
+    }
+}
@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+
+use crate::{DenyListEntry, ErrorCode};
+
+/// Space of a `DenyListEntry` account
+pub const DENY_LIST_ENTRY_SPACE: usize = 8 + 32 + 2 + 32 + 8 + 8 + 1;
+
+impl DenyListEntry {
+    /// Whether the entry still blocks its address at unix time `now`
+    pub fn is_active(&self, now: i64) -> bool {
+        self.expires_at == 0 || now < self.expires_at
+    }
+}
+
+/// Fail if `entry` holds an unexpired deny-list entry.
+///
+/// `entry` must already be seeds-checked as the `[DENY_LIST_SEED, address]` PDA. An
+/// account that was never created (or was closed by `remove_from_denylist`) means the
+/// address is not listed.
+pub fn check_not_denied(entry: &AccountInfo, now: i64) -> Result<()> {
+    if entry.owner != &crate::ID || entry.data_is_empty() {
+        return Ok(());
+    }
+    let entry = DenyListEntry::try_deserialize(&mut &entry.try_borrow_data()?[..])?;
+    require!(!entry.is_active(now), ErrorCode::DenyListBlocked);
+    Ok(())
+}
@@ -0,0 +1,178 @@
+use anchor_lang::prelude::*;
+use solana_instructions_sysvar::{load_current_index_checked, load_instruction_at_checked};
+use solana_sdk_ids::ed25519_program;
+
+use crate::ErrorCode;
+
+pub const PUBKEY_LEN: usize = 32;
+pub const SIGNATURE_LEN: usize = 64;
+/// `num_signatures: u8` followed by one padding byte
+pub const HEADER_LEN: usize = 2;
+/// Seven little-endian `u16` fields per signature
+pub const OFFSETS_LEN: usize = 14;
+/// Instruction index the precompile reads as "this instruction"
+pub const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+/// Per-signature offsets in Ed25519SigVerify instruction data
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Ed25519SignatureOffsets {
+    pub signature_offset: u16,
+    pub signature_instruction_index: u16,
+    pub public_key_offset: u16,
+    pub public_key_instruction_index: u16,
+    pub message_data_offset: u16,
+    pub message_data_size: u16,
+    pub message_instruction_index: u16,
+}
+
+impl Ed25519SignatureOffsets {
+    fn read(data: &[u8]) -> Self {
+        let field = |i: usize| u16::from_le_bytes([data[2 * i], data[2 * i + 1]]);
+        Self {
+            signature_offset: field(0),
+            signature_instruction_index: field(1),
+            public_key_offset: field(2),
+            public_key_instruction_index: field(3),
+            message_data_offset: field(4),
+            message_data_size: field(5),
+            message_instruction_index: field(6),
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; OFFSETS_LEN] {
+        let mut out = [0u8; OFFSETS_LEN];
+        for (i, v) in [
+            self.signature_offset,
+            self.signature_instruction_index,
+            self.public_key_offset,
+            self.public_key_instruction_index,
+            self.message_data_offset,
+            self.message_data_size,
+            self.message_instruction_index,
+        ]
+        .iter()
+        .enumerate()
+        {
+            out[2 * i..2 * i + 2].copy_from_slice(&v.to_le_bytes());
+        }
+        out
+    }
+}
+
+/// Data of the Ed25519SigVerify instruction immediately before the current one.
+///
+/// The runtime verifies precompile signatures before any program runs, so if this
+/// returns, every signature in the data is valid; callers still have to check *what*
+/// was signed with `ed25519_signed_messages`.
+pub fn load_ed25519_instruction(instructions_sysvar: &AccountInfo) -> Result<Vec<u8>> {
+    let current = load_current_index_checked(instructions_sysvar)?;
+    require!(current > 0, ErrorCode::InvalidAttestation);
+    let ix = load_instruction_at_checked(current as usize - 1, instructions_sysvar)?;
+    require_keys_eq!(ix.program_id, ed25519_program::ID, ErrorCode::InvalidAttestation);
+    Ok(ix.data)
+}
+
+/// Data of the contiguous run of Ed25519SigVerify instructions ending right before the
+/// current one, in transaction order. Used when several signers attest to one withdrawal.
+pub fn load_ed25519_instructions(instructions_sysvar: &AccountInfo) -> Result<Vec<Vec<u8>>> {
+    let current = load_current_index_checked(instructions_sysvar)? as usize;
+    let mut out = Vec::new();
+    for index in (0..current).rev() {
+        let ix = load_instruction_at_checked(index, instructions_sysvar)?;
+        if ix.program_id != ed25519_program::ID {
+            break;
+        }
+        out.push(ix.data);
+    }
+    require!(!out.is_empty(), ErrorCode::InvalidAttestation);
+    out.reverse();
+    Ok(out)
+}
+
+/// `(signer, message)` for every signature in Ed25519SigVerify instruction data.
+///
+/// Only self-contained instructions are accepted: offsets that point into another
+/// instruction (e.g. at bytes of our own instruction data) are rejected, so the
+/// signer and message read here are exactly what the precompile verified.
+pub fn ed25519_signed_messages(data: &[u8]) -> Result<Vec<(Pubkey, &[u8])>> {
+    require!(data.len() >= HEADER_LEN, ErrorCode::InvalidAttestation);
+    let count = data[0] as usize;
+    require!(count > 0, ErrorCode::InvalidAttestation);
+    require!(data.len() >= HEADER_LEN + count * OFFSETS_LEN, ErrorCode::InvalidAttestation);
+
+    let mut out = Vec::with_capacity(count);
+    for i in 0..count {
+        let start = HEADER_LEN + i * OFFSETS_LEN;
+        let offsets = Ed25519SignatureOffsets::read(&data[start..start + OFFSETS_LEN]);
+        require!(
+            offsets.signature_instruction_index == CURRENT_INSTRUCTION
+                && offsets.public_key_instruction_index == CURRENT_INSTRUCTION
+                && offsets.message_instruction_index == CURRENT_INSTRUCTION,
+            ErrorCode::InvalidAttestation
+        );
+
+        slice(data, offsets.signature_offset, SIGNATURE_LEN)?;
+        let pubkey = slice(data, offsets.public_key_offset, PUBKEY_LEN)?;
+        let message = slice(data, offsets.message_data_offset, offsets.message_data_size as usize)?;
+        out.push((Pubkey::try_from(pubkey).unwrap(), message));
+    }
+    Ok(out)
+}
+
+/// Require that the instruction data holds exactly one signature, by `signer` over `message`
+pub fn verify_ed25519_instruction(data: &[u8], signer: &Pubkey, message: &[u8]) -> Result<()> {
+    let signed = ed25519_signed_messages(data)?;
+    require!(signed.len() == 1, ErrorCode::InvalidAttestation);
+    let (key, msg) = signed[0];
+    require!(key == *signer && msg == message, ErrorCode::InvalidAttestation);
+    Ok(())
+}
+
+/// Distinct attesting signers across `instructions`.
+///
+/// Every signature must be over `message` and by a key in `allowed`; a key signing twice is
+/// rejected rather than counted once, so padding the transaction cannot inflate the count.
+pub fn attesting_signers(instructions: &[Vec<u8>], message: &[u8], allowed: &[Pubkey]) -> Result<Vec<Pubkey>> {
+    let mut signers: Vec<Pubkey> = Vec::new();
+    for data in instructions {
+        for (key, msg) in ed25519_signed_messages(data)? {
+            require!(msg == message, ErrorCode::InvalidAttestation);
+            require!(allowed.contains(&key), ErrorCode::Unauthorized);
+            require!(!signers.contains(&key), ErrorCode::DuplicateAttestation);
+            signers.push(key);
+        }
+    }
+    Ok(signers)
+}
+
+/// Build self-contained Ed25519SigVerify instruction data (the layout the
+/// `@solana/web3.js` `Ed25519Program` helper produces)
+pub fn ed25519_instruction_data(signer: &Pubkey, signature: &[u8; SIGNATURE_LEN], message: &[u8]) -> Vec<u8> {
+    let public_key_offset = HEADER_LEN + OFFSETS_LEN;
+    let signature_offset = public_key_offset + PUBKEY_LEN;
+    let message_data_offset = signature_offset + SIGNATURE_LEN;
+    let offsets = Ed25519SignatureOffsets {
+        signature_offset: signature_offset as u16,
+        signature_instruction_index: CURRENT_INSTRUCTION,
+        public_key_offset: public_key_offset as u16,
+        public_key_instruction_index: CURRENT_INSTRUCTION,
+        message_data_offset: message_data_offset as u16,
+        message_data_size: message.len() as u16,
+        message_instruction_index: CURRENT_INSTRUCTION,
+    };
+
+    let mut data = Vec::with_capacity(message_data_offset + message.len());
+    data.extend_from_slice(&[1u8, 0u8]);
+    data.extend_from_slice(&offsets.to_bytes());
+    data.extend_from_slice(signer.as_ref());
+    data.extend_from_slice(signature);
+    data.extend_from_slice(message);
+    data
+}
+
+fn slice(data: &[u8], offset: u16, len: usize) -> Result<&[u8]> {
+    let start = offset as usize;
+    let end = start + len;
+    require!(end <= data.len(), ErrorCode::InvalidAttestation);
+    Ok(&data[start..end])
+}
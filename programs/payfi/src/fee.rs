@@ -0,0 +1,13 @@
+use anchor_lang::prelude::*;
+
+use crate::{ErrorCode, MAX_FEE_BPS};
+
+/// Require `fee` to be at most `max_fee_bps` basis points of `amount`.
+///
+/// Computed in `u128` and rounded down, so a zero cap always forbids fees and a
+/// fee can never exceed the amount it is taken from.
+pub fn check_relayer_fee(amount: u64, fee: u64, max_fee_bps: u16) -> Result<()> {
+    let max_fee = (amount as u128) * (max_fee_bps.min(MAX_FEE_BPS) as u128) / (MAX_FEE_BPS as u128);
+    require!((fee as u128) <= max_fee, ErrorCode::FeeTooHigh);
+    Ok(())
+}
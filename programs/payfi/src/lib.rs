@@ -1,152 +1,531 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, TokenAccount, Transfer, Token};
-use anchor_lang::solana_program::{instruction::Instruction, program::invoke};
+use anchor_spl::token::{self, Mint, TokenAccount, Transfer, Token};
 // ComputeBudgetInstruction not available via re-export in some SDK versions; we'll build the instruction bytes manually.
+#[cfg(feature = "compute_budget")]
+use anchor_lang::solana_program::{instruction::Instruction, program::invoke};
+#[cfg(feature = "compute_budget")]
 use std::str::FromStr;
 
-// ed25519 syscall is intentionally not invoked here; signature checks are performed by length and relayer identity
-
 use anchor_lang::solana_program::sysvar::clock::Clock;
 
+pub mod attestation;
+pub mod authority;
+pub mod bond;
+pub mod deny_list;
+pub mod ed25519;
+pub mod fee;
+pub mod merkle;
+pub mod migration;
+pub mod nullifier;
+pub mod pause;
+pub mod public_inputs;
+pub mod rate_limit;
+pub mod registry;
+pub mod roles;
+pub mod sanctions;
+pub mod timelock;
+pub mod verification_key;
+
+use merkle::{MAX_ROOT_HISTORY_SIZE, MERKLE_TREE_DEPTH};
+use migration::{restore_deny_list, upgrade_admin, ADMIN_SPACE, ADMIN_VERSION, NULLIFIER_MANAGER_VERSION, POOL_CONFIG_SPACE, POOL_CONFIG_VERSION, TREE_STATE_VERSION, VAULT_VERSION};
+use attestation::{check_attestation_versions, Attestation};
+use bond::{check_unbonding_period, DEFAULT_UNBONDING_PERIOD};
+use deny_list::{check_not_denied, DENY_LIST_ENTRY_SPACE};
+use ed25519::{attesting_signers, load_ed25519_instruction, load_ed25519_instructions, verify_ed25519_instruction};
+use fee::check_relayer_fee;
+use pause::{PAUSE_ALL, PAUSE_DEPOSITS, PAUSE_RELAYER_WITHDRAWALS, PAUSE_WITHDRAWALS};
+use public_inputs::WithdrawPublicInputs;
+use registry::active_relayers;
+use roles::{ALL_ROLES, ROLE_COMPLIANCE, ROLE_PAUSER, ROLE_RELAYER_MANAGER};
+use timelock::{ConfigChange, MAX_TIMELOCK_DELAY};
+use sanctions::{verify_non_membership, SanctionsProof, DENY_LIST_MODE_PDA, DENY_LIST_MODE_SANCTIONS_ROOT};
+use verification_key::check_verification_key;
+use verifier::groth16::Groth16Proof;
+use verifier::ProofBuffer;
+
 declare_id!("7SU3shMVxuzrQa614tkoQqicKPe1U9BFRJRzXoemFaeX");
 
 pub const VAULT_SEED: &[u8] = b"vault";
 pub const TREE_STATE_SEED: &[u8] = b"tree_state";
 pub const NULLIFIER_SET_SEED: &[u8] = b"nullifier_set";
-pub const NULLIFIER_CHUNK_SEED: &[u8] = b"nullifier_chunk";
+pub const NULLIFIER_MANAGER_SEED: &[u8] = b"nullifier_manager";
+pub const NULLIFIER_SEED: &[u8] = b"nullifier";
 pub const ADMIN_SEED: &[u8] = b"admin";
+pub const DENY_LIST_SEED: &[u8] = b"deny_list";
+pub const ROLE_SEED: &[u8] = b"role";
+pub const PENDING_CHANGE_SEED: &[u8] = b"pending_change";
 pub const RELAYER_STATE_SEED: &[u8] = b"relayer_state";
+pub const RELAYER_BOND_SEED: &[u8] = b"relayer_bond";
+pub const POOL_CONFIG_SEED: &[u8] = b"pool_config";
+
+/// 100% in basis points
+pub const MAX_FEE_BPS: u16 = 10_000;
 
 #[program]
 pub mod payfi {
     use super::*;
 
-    pub fn initialize(ctx: Context<Initialize>, admin: Pubkey, vault_token_account: Pubkey, vault_bump: u8, tree_bump: u8, max_chunks: u64, admin_bump: u8) -> Result<()> {
+    /// Create the program-wide admin account; token pools are added with `initialize_pool`
+    pub fn initialize(ctx: Context<Initialize>, admin: Pubkey, admin_bump: u8, timelock_delay: u64) -> Result<()> {
+        require!(timelock_delay <= MAX_TIMELOCK_DELAY, ErrorCode::InvalidConfigChange);
+
         let admin_account = &mut ctx.accounts.admin;
+        admin_account.version = ADMIN_VERSION;
         admin_account.authority = admin;
-        admin_account.deny_list = vec![];
-        admin_account.verifier_mode = 0u8; // 0 = off, 1 = stub, 2 = CPI
-        admin_account.verifier_magic = vec![];
-        admin_account.paused = false;
+        admin_account.pending_authority = None;
+        admin_account.unbonding_period = DEFAULT_UNBONDING_PERIOD;
+        admin_account.timelock_delay = timelock_delay;
+        admin_account.next_change_id = 0;
         admin_account.bump = admin_bump;
 
-        let vault_account = &mut ctx.accounts.vault;
-        vault_account.token_account = vault_token_account;
-        vault_account.bump = vault_bump;
+        msg!("PayFi initialized by: {:?}", admin);
+        Ok(())
+    }
+
+    /// Open a shielded pool for `mint`. Each mint gets its own vault, tree, nullifier set
+    /// and `PoolConfig`, so notes deposited in one pool can only be withdrawn from that pool.
+    /// The pool starts with its verifier off, which rejects direct withdrawals until a
+    /// queued `VerifierMode` change turns verification on.
+    pub fn initialize_pool(ctx: Context<InitializePool>, mint: Pubkey, root_history_size: u16) -> Result<()> {
+        require!(root_history_size > 0 && root_history_size <= MAX_ROOT_HISTORY_SIZE, ErrorCode::InvalidRootHistorySize);
+
+        let vault = &mut ctx.accounts.vault;
+        vault.version = VAULT_VERSION;
+        vault.mint = mint;
+        vault.token_account = ctx.accounts.vault_token_account.key();
+        vault.bump = ctx.bumps.vault;
 
         let tree = &mut ctx.accounts.tree_state;
-        tree.root = [0u8;32];
-        tree.bump = tree_bump;
+        tree.init_tree(root_history_size as usize);
+        tree.version = TREE_STATE_VERSION;
+        tree.bump = ctx.bumps.tree_state;
 
         let manager = &mut ctx.accounts.nullifier_manager;
+        manager.version = NULLIFIER_MANAGER_VERSION;
         manager.count = 0u64;
-        manager.max_chunks = max_chunks;
-        manager.bump = 0u8; // bump left zero; clients can derive PDA bump
-
-        // No chunks created by default; use `init_nullifier_chunk` to create chunk accounts on-demand
-
-        msg!("PayFi initialized by: {:?}", admin);
+        manager.bump = ctx.bumps.nullifier_manager;
+
+        let config = &mut ctx.accounts.pool_config;
+        config.version = POOL_CONFIG_VERSION;
+        config.mint = mint;
+        config.deny_list_mode = DENY_LIST_MODE_PDA;
+        config.sanctions_root = [0u8; 32];
+        config.verifier_mode = 0u8; // 0 = off (no direct withdrawals), 1 = stub, 2 = CPI
+        config.verifier_magic = vec![];
+        config.verifier_program = Pubkey::default();
+        config.verification_key = Pubkey::default();
+        config.verification_key_hash = [0u8; 32];
+        config.pause_flags = 0;
+        config.paused_until = 0;
+        config.attestation_threshold = 1;
+        config.threshold_amount = u64::MAX; // threshold mode off until configured
+        config.max_fee_bps = 0; // no relayer fees until configured
+        config.relayer_count = 0;
+        config.min_relayer_bond = 0; // any non-zero bond until configured
+        config.bump = ctx.bumps.pool_config;
+
+        emit!(PoolInitializedEvent { mint, vault: vault.key(), token_account: vault.token_account });
         Ok(())
     }
 
-    pub fn set_verifier_mode(ctx: Context<SetVerifierMode>, mode: u8, magic: Option<Vec<u8>>) -> Result<()> {
-        let admin = &mut ctx.accounts.admin;
-        require!(ctx.accounts.authority.key() == admin.authority, ErrorCode::Unauthorized);
-        admin.verifier_mode = mode;
-        admin.verifier_magic = magic.unwrap_or_default();
+    /// Rewrite the admin account in the current layout after a program upgrade. The
+    /// account is resized in place; the authority tops up rent when it grows and gets
+    /// the surplus back when it shrinks. Legacy deny-list entries are recreated as PDAs:
+    /// pass their `[DENY_LIST_SEED, address]` accounts as remaining accounts, in order.
+    /// The legacy `[VAULT_SEED]` vault is left alone: the unversioned program kept no note
+    /// tree, so its notes have no claim path in a per-mint pool yet.
+    pub fn migrate_admin<'info>(ctx: Context<'_, '_, 'info, 'info, MigrateAdmin<'info>>) -> Result<()> {
+        let info = ctx.accounts.admin.to_account_info();
+        let migration = upgrade_admin(&info.try_borrow_data()?)?;
+        require_keys_eq!(ctx.accounts.authority.key(), migration.admin.authority, ErrorCode::Unauthorized);
+
+        let rent = Rent::get()?.minimum_balance(ADMIN_SPACE);
+        let lamports = info.lamports();
+        if rent > lamports {
+            let cpi_accounts = anchor_lang::system_program::Transfer {
+                from: ctx.accounts.authority.to_account_info(),
+                to: info.clone(),
+            };
+            let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts);
+            anchor_lang::system_program::transfer(cpi_ctx, rent - lamports)?;
+        } else if lamports > rent {
+            info.sub_lamports(lamports - rent)?;
+            ctx.accounts.authority.add_lamports(lamports - rent)?;
+        }
+        info.resize(ADMIN_SPACE)?;
+        migration.admin.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+        restore_deny_list(
+            &migration.legacy_deny_list,
+            ctx.remaining_accounts,
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            Clock::get()?.unix_timestamp,
+        )?;
+
+        emit!(AdminMigratedEvent {
+            from_version: migration.from_version,
+            to_version: ADMIN_VERSION,
+            legacy_relayers: migration.legacy_relayers,
+            legacy_deny_list: migration.legacy_deny_list,
+            legacy_paused: migration.legacy_paused,
+            legacy_verifier_mode: migration.legacy_verifier_mode,
+            legacy_verifier_magic: migration.legacy_verifier_magic,
+        });
         Ok(())
     }
 
-    pub fn add_to_denylist(ctx: Context<ModifyDenyList>, addr: Pubkey) -> Result<()> {
+    /// Queue a sensitive configuration change; it can be executed once
+    /// `admin.timelock_delay` seconds have passed
+    pub fn queue_change(ctx: Context<QueueChange>, change: ConfigChange) -> Result<()> {
+        change.validate()?;
         let admin = &mut ctx.accounts.admin;
-        require!(ctx.accounts.authority.key() == admin.authority, ErrorCode::Unauthorized);
-        if !admin.deny_list.iter().any(|a| a == &addr) {
-            admin.deny_list.push(addr);
+        let signer = ctx.accounts.authority.key();
+        match change.role() {
+            Some(role) => admin.require_role(&signer, ctx.accounts.role.as_deref(), role)?,
+            None => require_keys_eq!(signer, admin.authority, ErrorCode::Unauthorized),
         }
+
+        let now = Clock::get()?.unix_timestamp;
+        let id = admin.next_change_id;
+        admin.next_change_id = id.checked_add(1).unwrap();
+
+        let pending = &mut ctx.accounts.pending_change;
+        pending.id = id;
+        pending.change = change.clone();
+        pending.queued_by = signer;
+        pending.queued_at = now;
+        pending.eta = now.saturating_add(admin.timelock_delay as i64);
+        pending.bump = ctx.bumps.pending_change;
+
+        emit!(ChangeQueuedEvent { id, change, queued_by: signer, eta: pending.eta });
         Ok(())
     }
 
-    pub fn remove_from_denylist(ctx: Context<ModifyDenyList>, addr: Pubkey) -> Result<()> {
+    /// Apply a queued change whose delay has passed. Anyone may execute; the rent goes
+    /// back to whoever queued it. Every pool-scoped change needs the target pool's
+    /// `pool_config`; `AddRelayer`/`RemoveRelayer` also need `relayer_record` and
+    /// `VerifierProgram` needs `verifier_program` and `verification_key`.
+    pub fn execute_change(ctx: Context<ExecuteChange>, id: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(ctx.accounts.pending_change.is_ready(now), ErrorCode::TimelockNotElapsed);
+
         let admin = &mut ctx.accounts.admin;
-        require!(ctx.accounts.authority.key() == admin.authority, ErrorCode::Unauthorized);
-        admin.deny_list.retain(|a| a != &addr);
+        match ctx.accounts.pending_change.change.clone() {
+            ConfigChange::VerifierMode { mint, mode, magic } => {
+                let config = ctx.accounts.pool_config.as_mut().ok_or(ErrorCode::InvalidPoolConfig)?;
+                require_keys_eq!(config.mint, mint, ErrorCode::InvalidPoolConfig);
+                // mode 2 CPIs into the pinned program, so it must be pinned first
+                require!(mode != 2 || config.verifier_program != Pubkey::default(), ErrorCode::InvalidVerifierProgram);
+                config.verifier_mode = mode;
+                config.verifier_magic = magic;
+            }
+            ConfigChange::VerifierProgram { mint, program, verification_key, verification_key_hash } => {
+                let config = ctx.accounts.pool_config.as_mut().ok_or(ErrorCode::InvalidPoolConfig)?;
+                require_keys_eq!(config.mint, mint, ErrorCode::InvalidPoolConfig);
+                let verifier = ctx.accounts.verifier_program.as_ref().ok_or(ErrorCode::InvalidVerifierProgram)?;
+                require_keys_eq!(verifier.key(), program, ErrorCode::InvalidVerifierProgram);
+                require!(verifier.executable, ErrorCode::InvalidVerifierProgram);
+                // the key must already be registered with that verifier, with the queued contents
+                let key = ctx.accounts.verification_key.as_ref().ok_or(ErrorCode::InvalidVerificationKey)?;
+                require_keys_eq!(key.key(), verification_key, ErrorCode::InvalidVerificationKey);
+                check_verification_key(key, &program, &verification_key_hash)?;
+                config.verifier_program = program;
+                config.verification_key = verification_key;
+                config.verification_key_hash = verification_key_hash;
+            }
+            ConfigChange::AddRelayer { mint, relayer } => {
+                let config = ctx.accounts.pool_config.as_mut().ok_or(ErrorCode::InvalidPoolConfig)?;
+                require_keys_eq!(config.mint, mint, ErrorCode::InvalidPoolConfig);
+                let record = ctx.accounts.relayer_record.as_mut().ok_or(ErrorCode::Unauthorized)?;
+                require_keys_eq!(record.relayer, relayer, ErrorCode::Unauthorized);
+                require_keys_eq!(record.mint, mint, ErrorCode::Unauthorized);
+                require!(record.is_bonded(config.min_relayer_bond), ErrorCode::InsufficientRelayerBond);
+                record.activate(config);
+            }
+            ConfigChange::RemoveRelayer { mint, relayer } => {
+                let config = ctx.accounts.pool_config.as_mut().ok_or(ErrorCode::InvalidPoolConfig)?;
+                require_keys_eq!(config.mint, mint, ErrorCode::InvalidPoolConfig);
+                let record = ctx.accounts.relayer_record.as_mut().ok_or(ErrorCode::Unauthorized)?;
+                require_keys_eq!(record.relayer, relayer, ErrorCode::Unauthorized);
+                require_keys_eq!(record.mint, mint, ErrorCode::Unauthorized);
+                record.deactivate(config);
+            }
+            ConfigChange::ProposeAuthority { new_authority } => {
+                admin.propose_authority(new_authority)?;
+                emit!(AuthorityProposedEvent { authority: admin.authority, proposed: new_authority });
+            }
+            ConfigChange::TimelockDelay { delay } => {
+                admin.timelock_delay = delay;
+            }
+        }
+
+        emit!(ChangeExecutedEvent { id, executed_by: ctx.accounts.executor.key() });
         Ok(())
     }
 
-    pub fn add_relayer(ctx: Context<ModifyRelayer>, addr: Pubkey) -> Result<()> {
-        let admin = &mut ctx.accounts.admin;
-        require!(ctx.accounts.authority.key() == admin.authority, ErrorCode::Unauthorized);
-        if !admin.relayers.iter().any(|a| a == &addr) {
-            admin.relayers.push(addr);
+    /// Drop a queued change before it is executed
+    pub fn cancel_change(ctx: Context<CancelChange>, id: u64) -> Result<()> {
+        let admin = &ctx.accounts.admin;
+        let signer = ctx.accounts.authority.key();
+        match ctx.accounts.pending_change.change.role() {
+            Some(role) => admin.require_role(&signer, ctx.accounts.role.as_deref(), role)?,
+            None => require_keys_eq!(signer, admin.authority, ErrorCode::Unauthorized),
         }
+        emit!(ChangeCancelledEvent { id, cancelled_by: signer });
+        Ok(())
+    }
+
+    /// Grant `roles` (a bitmask of `roles::ROLE_*`) to `holder`, keeping any it already has
+    pub fn grant_role(ctx: Context<GrantRole>, holder: Pubkey, roles: u8) -> Result<()> {
+        require!(roles != 0 && roles & !ALL_ROLES == 0, ErrorCode::InvalidRole);
+        let assignment = &mut ctx.accounts.role;
+        assignment.holder = holder;
+        assignment.roles |= roles;
+        assignment.bump = ctx.bumps.role;
+        emit!(RolesChangedEvent { holder, roles: assignment.roles });
+        Ok(())
+    }
+
+    pub fn revoke_role(ctx: Context<RevokeRole>, holder: Pubkey, roles: u8) -> Result<()> {
+        let assignment = &mut ctx.accounts.role;
+        assignment.roles &= !roles;
+        emit!(RolesChangedEvent { holder, roles: assignment.roles });
+        Ok(())
+    }
+
+    /// Second step of an authority transfer queued as `ConfigChange::ProposeAuthority`:
+    /// the pending authority signs to take control
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        let new_authority = ctx.accounts.new_authority.key();
+        let previous = ctx.accounts.admin.accept_authority(new_authority)?;
+        emit!(AuthorityTransferredEvent { previous, authority: new_authority });
         Ok(())
     }
 
-    pub fn remove_relayer(ctx: Context<ModifyRelayer>, addr: Pubkey) -> Result<()> {
+    pub fn cancel_authority_transfer(ctx: Context<CancelAuthorityTransfer>) -> Result<()> {
         let admin = &mut ctx.accounts.admin;
-        require!(ctx.accounts.authority.key() == admin.authority, ErrorCode::Unauthorized);
-        admin.relayers.retain(|a| a != &addr);
+        let cancelled = admin.cancel_authority_transfer()?;
+        emit!(AuthorityTransferCancelledEvent { authority: admin.authority, cancelled });
+        Ok(())
+    }
+
+    /// Block `addr` from depositing and receiving withdrawals. `reason` is a compliance
+    /// reason code; `expires_at` is a unix timestamp, or 0 for no expiry.
+    pub fn add_to_denylist(ctx: Context<AddToDenyList>, addr: Pubkey, reason: u16, expires_at: i64) -> Result<()> {
+        ctx.accounts.admin.require_role(&ctx.accounts.authority.key(), ctx.accounts.role.as_deref(), ROLE_COMPLIANCE)?;
+        let now = Clock::get()?.unix_timestamp;
+        require!(expires_at == 0 || expires_at > now, ErrorCode::InvalidDenyListExpiry);
+
+        let entry = &mut ctx.accounts.deny_list_entry;
+        entry.address = addr;
+        entry.reason = reason;
+        entry.added_by = ctx.accounts.authority.key();
+        entry.added_at = now;
+        entry.expires_at = expires_at;
+        entry.bump = ctx.bumps.deny_list_entry;
+
+        emit!(DenyListAddedEvent { address: addr, reason, added_by: entry.added_by, expires_at });
+        Ok(())
+    }
+
+    /// Choose how the pool's `deposit` screens depositors: per-address PDAs or a
+    /// sanctions-list root
+    pub fn set_deny_list_mode(ctx: Context<SetDenyListMode>, mode: u8) -> Result<()> {
+        ctx.accounts.admin.require_role(&ctx.accounts.authority.key(), ctx.accounts.role.as_deref(), ROLE_COMPLIANCE)?;
+        require!(mode == DENY_LIST_MODE_PDA || mode == DENY_LIST_MODE_SANCTIONS_ROOT, ErrorCode::InvalidDenyListMode);
+        ctx.accounts.pool_config.deny_list_mode = mode;
+        Ok(())
+    }
+
+    /// Replace the pool's sanctions-list root (see `sanctions` for how the tree is built)
+    pub fn set_sanctions_root(ctx: Context<SetDenyListMode>, root: [u8;32]) -> Result<()> {
+        ctx.accounts.admin.require_role(&ctx.accounts.authority.key(), ctx.accounts.role.as_deref(), ROLE_COMPLIANCE)?;
+        ctx.accounts.pool_config.sanctions_root = root;
+        emit!(SanctionsRootUpdatedEvent { mint: ctx.accounts.pool_config.mint, root, updated_by: ctx.accounts.authority.key() });
         Ok(())
     }
 
-    #[derive(Accounts)]
-    #[instruction(relayer: Pubkey)]
-    pub struct InitRelayerState<'info> {
-        #[account(init, payer = payer, space = 8 + 8 + 8 + 8 + 8 + 1, seeds = [RELAYER_STATE_SEED, relayer.as_ref()], bump)]
-        pub relayer_state: Account<'info, RelayerState>,
-        #[account(mut)]
-        pub payer: Signer<'info>,
-        pub system_program: Program<'info, System>,
+    /// Unblock `addr`; the entry is closed and its rent returned to the authority
+    pub fn remove_from_denylist(ctx: Context<RemoveFromDenyList>, addr: Pubkey) -> Result<()> {
+        ctx.accounts.admin.require_role(&ctx.accounts.authority.key(), ctx.accounts.role.as_deref(), ROLE_COMPLIANCE)?;
+        emit!(DenyListRemovedEvent { address: addr, removed_by: ctx.accounts.authority.key() });
+        Ok(())
     }
 
-    pub fn init_relayer_state(ctx: Context<InitRelayerState>, relayer: Pubkey, limit: u64, window_seconds: u64) -> Result<()> {
-        let state = &mut ctx.accounts.relayer_state;
+    /// Create the registry record and bond escrow for `relayer` in the pool of `vault`: at
+    /// most `limit` withdrawals and `amount_limit` tokens per `window_seconds`. The relayer
+    /// starts inactive until bonded and added with a queued `ConfigChange::AddRelayer`.
+    pub fn init_relayer_record(ctx: Context<InitRelayerRecord>, relayer: Pubkey, limit: u64, window_seconds: u64, amount_limit: u64) -> Result<()> {
+        ctx.accounts.admin.require_role(&ctx.accounts.authority.key(), ctx.accounts.role.as_deref(), ROLE_RELAYER_MANAGER)?;
+        require!(window_seconds > 0, ErrorCode::InvalidRelayerLimits);
+        let state = &mut ctx.accounts.relayer_record;
+        state.relayer = relayer;
+        state.mint = ctx.accounts.vault.mint;
+        state.active = false;
+        state.metadata_url_hash = [0u8; 32];
+        state.fee_bps = MAX_FEE_BPS; // only the pool's max fee applies until the relayer sets its own
         state.window_start = 0u64;
         state.count = 0u64;
+        state.amount = 0u64;
         state.limit = limit;
         state.window_seconds = window_seconds;
-        // derive bump and store it so clients can use it in constraints
-        let (_pda, bump) = Pubkey::find_program_address(&[RELAYER_STATE_SEED, relayer.as_ref()], ctx.program_id);
-        state.bump = bump;
+        state.amount_limit = amount_limit;
+        state.bond = 0u64;
+        state.unbond_ready_at = 0u64;
+        state.total_withdrawals = 0u64;
+        state.total_volume = 0u64;
+        state.total_fees = 0u64;
+        state.bump = ctx.bumps.relayer_record;
         Ok(())
     }
 
-    pub fn set_pause(ctx: Context<SetPause>, paused: bool) -> Result<()> {
-        let admin = &mut ctx.accounts.admin;
-        require!(ctx.accounts.authority.key() == admin.authority, ErrorCode::Unauthorized);
-        admin.paused = paused;
+    /// Relayer-managed profile: hash of its off-chain metadata URL and the most it
+    /// charges, in basis points (the pool's `max_fee_bps` still applies)
+    pub fn update_relayer_profile(ctx: Context<UpdateRelayerProfile>, metadata_url_hash: [u8;32], fee_bps: u16) -> Result<()> {
+        require!(fee_bps <= MAX_FEE_BPS, ErrorCode::FeeTooHigh);
+        let record = &mut ctx.accounts.relayer_record;
+        record.metadata_url_hash = metadata_url_hash;
+        record.fee_bps = fee_bps;
         Ok(())
     }
 
-    /// Initialize a Nullifier chunk account for a specific chunk index.
-    pub fn init_nullifier_chunk(ctx: Context<InitNullifierChunk>, index: u64) -> Result<()> {
-        // enforce manager limits
-        let manager = &mut ctx.accounts.manager;
-        require!(manager.count < manager.max_chunks, ErrorCode::ChunkLimitReached);
+    /// Deposit `amount` into the relayer's bond escrow
+    pub fn bond_relayer(ctx: Context<BondRelayer>, amount: u64) -> Result<()> {
+        ctx.accounts.relayer_record.add_bond(amount)?;
 
-        let chunk = &mut ctx.accounts.chunk;
-        chunk.index = index;
-        chunk.bitmap = [0u8; 32];
-        // store bump as 0 for now (clients can derive bump)
-        chunk.bump = 0u8;
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.relayer_token_account.to_account_info(),
+            to: ctx.accounts.bond_escrow.to_account_info(),
+            authority: ctx.accounts.relayer.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::transfer(CpiContext::new(cpi_program, cpi_accounts), amount)?;
+        Ok(())
+    }
 
-        manager.count = manager.count.checked_add(1).unwrap();
+    /// Move `amount` of a relayer's bond into the vault. A relayer whose bond drops below
+    /// the pool's `min_relayer_bond` is deregistered.
+    pub fn slash_relayer(ctx: Context<SlashRelayer>, relayer: Pubkey, amount: u64) -> Result<()> {
+        ctx.accounts.admin.require_role(&ctx.accounts.authority.key(), ctx.accounts.role.as_deref(), ROLE_RELAYER_MANAGER)?;
+        let state = &mut ctx.accounts.relayer_record;
+        state.slash(amount)?;
+        let remaining = state.bond;
+
+        let config = &mut ctx.accounts.pool_config;
+        if !state.is_bonded(config.min_relayer_bond) {
+            state.deactivate(config);
+        }
+
+        let vault_seeds: &[&[u8]] = &[VAULT_SEED, ctx.accounts.vault.mint.as_ref(), &[ctx.accounts.vault.bump]];
+        let signer_seeds: &[&[&[u8]]] = &[vault_seeds];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.bond_escrow.to_account_info(),
+            to: ctx.accounts.vault_token_account.to_account_info(),
+            authority: ctx.accounts.vault.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::transfer(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds), amount)?;
+
+        emit!(RelayerSlashedEvent { mint: ctx.accounts.vault.mint, relayer, amount, remaining });
         Ok(())
     }
 
-    /// Update the on-chain merkle/compression root (Light Compression stub)
-    pub fn update_root(ctx: Context<UpdateRoot>, new_root: [u8;32]) -> Result<()> {
-        let admin = &ctx.accounts.admin;
-        require!(ctx.accounts.authority.key() == admin.authority, ErrorCode::Unauthorized);
-        let tree = &mut ctx.accounts.tree_state;
-        tree.root = new_root;
+    /// Stop relaying and start the unbonding period. The relayer is deregistered at once
+    /// but the bond stays slashable until `withdraw_bond`.
+    pub fn request_unbond(ctx: Context<RequestUnbond>) -> Result<()> {
+        let clock = Clock::get()?;
+        let record = &mut ctx.accounts.relayer_record;
+        record.request_unbond(clock.unix_timestamp, ctx.accounts.admin.unbonding_period)?;
+        record.deactivate(&mut ctx.accounts.pool_config);
+        Ok(())
+    }
+
+    /// Return the remaining bond once the unbonding period has passed
+    pub fn withdraw_bond(ctx: Context<WithdrawBond>) -> Result<()> {
+        let clock = Clock::get()?;
+        let amount = ctx.accounts.relayer_record.release_bond(clock.unix_timestamp)?;
+
+        let vault_seeds: &[&[u8]] = &[VAULT_SEED, ctx.accounts.vault.mint.as_ref(), &[ctx.accounts.vault.bump]];
+        let signer_seeds: &[&[&[u8]]] = &[vault_seeds];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.bond_escrow.to_account_info(),
+            to: ctx.accounts.relayer_token_account.to_account_info(),
+            authority: ctx.accounts.vault.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::transfer(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds), amount)?;
+        Ok(())
+    }
+
+    /// Minimum bond for registration in the pool and the program-wide delay between
+    /// `request_unbond` and `withdraw_bond`
+    pub fn set_bond_params(ctx: Context<SetBondParams>, min_relayer_bond: u64, unbonding_period: u64) -> Result<()> {
+        ctx.accounts.admin.require_role(&ctx.accounts.authority.key(), ctx.accounts.role.as_deref(), ROLE_RELAYER_MANAGER)?;
+        check_unbonding_period(unbonding_period)?;
+        ctx.accounts.pool_config.min_relayer_bond = min_relayer_bond;
+        ctx.accounts.admin.unbonding_period = unbonding_period;
+        Ok(())
+    }
+
+    /// Adjust a relayer's limits; the current window's usage is kept
+    pub fn set_relayer_limits(ctx: Context<SetRelayerLimits>, _relayer: Pubkey, limit: u64, window_seconds: u64, amount_limit: u64) -> Result<()> {
+        ctx.accounts.admin.require_role(&ctx.accounts.authority.key(), ctx.accounts.role.as_deref(), ROLE_RELAYER_MANAGER)?;
+        require!(window_seconds > 0, ErrorCode::InvalidRelayerLimits);
+        let state = &mut ctx.accounts.relayer_record;
+        state.limit = limit;
+        state.window_seconds = window_seconds;
+        state.amount_limit = amount_limit;
+        Ok(())
+    }
+
+    /// Replace the pool's pause bitfield (`pause::PAUSE_*`; 0 unpauses everything). With a
+    /// non-zero `paused_until` the pause lifts itself at that unix timestamp.
+    pub fn set_pause(ctx: Context<SetPause>, flags: u8, paused_until: i64) -> Result<()> {
+        ctx.accounts.admin.require_role(&ctx.accounts.authority.key(), ctx.accounts.role.as_deref(), ROLE_PAUSER)?;
+        require!(flags & !PAUSE_ALL == 0, ErrorCode::InvalidPauseFlags);
+        let now = Clock::get()?.unix_timestamp;
+        require!(paused_until == 0 || paused_until > now, ErrorCode::InvalidPauseFlags);
+
+        let config = &mut ctx.accounts.pool_config;
+        config.pause_flags = flags;
+        config.paused_until = paused_until;
+        emit!(PauseChangedEvent { mint: config.mint, flags, paused_until, changed_by: ctx.accounts.authority.key() });
         Ok(())
     }
 
-    pub fn deposit(ctx: Context<Deposit>, amount: u64, commitment: [u8;32], encrypted_note: Option<Vec<u8>>) -> Result<()> {
+    /// Require `threshold` distinct registered relayers to attest to withdrawals above
+    /// `threshold_amount`
+    pub fn set_attestation_threshold(ctx: Context<SetAttestationThreshold>, threshold: u8, threshold_amount: u64) -> Result<()> {
+        ctx.accounts.admin.require_role(&ctx.accounts.authority.key(), ctx.accounts.role.as_deref(), ROLE_RELAYER_MANAGER)?;
+        let config = &mut ctx.accounts.pool_config;
+        require!(threshold >= 1 && threshold as u32 <= config.relayer_count, ErrorCode::InvalidAttestationThreshold);
+        config.attestation_threshold = threshold;
+        config.threshold_amount = threshold_amount;
+        Ok(())
+    }
+
+    /// Cap the pool's relayer fees at `max_fee_bps` basis points of the withdrawn amount
+    pub fn set_max_fee_bps(ctx: Context<SetMaxFeeBps>, max_fee_bps: u16) -> Result<()> {
+        ctx.accounts.admin.require_role(&ctx.accounts.authority.key(), ctx.accounts.role.as_deref(), ROLE_RELAYER_MANAGER)?;
+        require!(max_fee_bps <= MAX_FEE_BPS, ErrorCode::FeeTooHigh);
+        ctx.accounts.pool_config.max_fee_bps = max_fee_bps;
+        Ok(())
+    }
+
+    /// `sanctions_proof` is required when the pool screens depositors against a sanctions root
+    pub fn deposit(ctx: Context<Deposit>, amount: u64, commitment: [u8;32], encrypted_note: Option<Vec<u8>>, sanctions_proof: Option<SanctionsProof>) -> Result<()> {
+        let config = &ctx.accounts.pool_config;
+        let now = Clock::get()?.unix_timestamp;
+        config.require_not_paused(PAUSE_DEPOSITS, now)?;
+
         // Deny-list check: disallow depositors on deny list
-        let admin = &ctx.accounts.admin;
-        require!(!admin.deny_list.iter().any(|a| a == &ctx.accounts.user.key()), ErrorCode::DenyListBlocked);
+        if config.deny_list_mode == DENY_LIST_MODE_SANCTIONS_ROOT {
+            let proof = sanctions_proof.ok_or(ErrorCode::InvalidSanctionsProof)?;
+            verify_non_membership(&config.sanctions_root, &ctx.accounts.user.key(), &proof)?;
+        } else {
+            check_not_denied(&ctx.accounts.deny_list_entry, now)?;
+        }
 
         // Transfer tokens from user to vault
         let cpi_accounts = Transfer {
@@ -157,66 +536,91 @@ pub mod payfi {
         let cpi_program = ctx.accounts.token_program.to_account_info();
         token::transfer(CpiContext::new(cpi_program, cpi_accounts), amount)?;
 
-        // TODO: integrate with Light ZK Compression / append commitment
-        ctx.accounts.tree_state.root = commitment; // placeholder behaviour
+        // Append commitment as the next leaf of the incremental Merkle tree
+        let tree = &mut ctx.accounts.tree_state;
+        let leaf_index = tree.append_leaf(commitment)?;
+        let root = tree.root;
 
-        // Emit encrypted note event (for auditors with view keys)
-        if let Some(enc) = encrypted_note {
-            emit!(EncryptedNoteEvent { commitment, encrypted: enc });
-        } else {
-            emit!(EncryptedNoteEvent { commitment, encrypted: vec![] });
-        }
+        // Emit encrypted note event (for auditors with view keys); leaf_index lets clients rebuild paths
+        emit!(EncryptedNoteEvent { commitment, leaf_index, root, encrypted: encrypted_note.unwrap_or_default() });
 
         Ok(())
     }
 
+    /// Pass the proof inline, or leave `proof` empty and supply a verifier `ProofBuffer`
+    /// for proofs that do not fit in one transaction.
     pub fn withdraw(ctx: Context<Withdraw>, proof: Vec<u8>, nullifier: [u8;32], root: [u8;32], amount: u64) -> Result<()> {
-        let admin = &mut ctx.accounts.admin;
+        let proof = match &ctx.accounts.proof_buffer {
+            Some(buffer) => {
+                require!(proof.is_empty(), ErrorCode::InvalidProof);
+                buffer.data.clone()
+            }
+            None => proof,
+        };
+
+        let config = &ctx.accounts.pool_config;
         // pause check
-        require!(!admin.paused, ErrorCode::ContractPaused);
+        config.require_not_paused(PAUSE_WITHDRAWALS, Clock::get()?.unix_timestamp)?;
 
         // recipient deny-list check
-        require!(!admin.deny_list.iter().any(|a| a == &ctx.accounts.recipient_token_account.owner), ErrorCode::DenyListBlocked);
-
-        // Verify root
-        require!(ctx.accounts.tree_state.root == root, ErrorCode::RootMismatch);
+        check_not_denied(&ctx.accounts.deny_list_entry, Clock::get()?.unix_timestamp)?;
+
+        // Verify root is current or still within the recent root history window
+        require!(ctx.accounts.tree_state.is_known_root(&root), ErrorCode::RootMismatch);
+
+        // The proof commits to where the funds go; direct withdrawals pay no relayer fee
+        let public_inputs = WithdrawPublicInputs {
+            root,
+            nullifier,
+            recipient: ctx.accounts.recipient_token_account.key(),
+            amount,
+            relayer: Pubkey::default(),
+            fee: 0,
+        };
 
         // Proof verification paths:
+        // mode 0: off, no proof is accepted
         // mode 1: internal stub (proof must match magic)
         // mode 2: CPI to verifier program
-        // mode 3: relayer mode (disallow direct calls; relayer must use withdraw_by_relayer)
-        if admin.verifier_mode == 1u8 {
-            require!(admin.verifier_magic.len() > 0 && proof == admin.verifier_magic, ErrorCode::InvalidProof);
-        } else if admin.verifier_mode == 2u8 {
-            // CPI to verifier program
+        require!(config.verifier_mode != 0, ErrorCode::VerifierNotConfigured);
+        if config.verifier_mode == 1u8 {
+            require!(!config.verifier_magic.is_empty() && proof == config.verifier_magic, ErrorCode::InvalidProof);
+        } else if config.verifier_mode == 2u8 {
+            // CPI to the pinned verifier program against the pinned verifying key; the proof is A || B || C
             let verifier = &ctx.accounts.verifier_program;
-            let ix = Instruction::new_with_borsh(
-                verifier.key(),
-                &(proof.clone(), admin.verifier_magic.clone()),
-                vec![],
-            );
-            invoke(&ix, &[verifier.to_account_info()])?;
+            require_keys_eq!(verifier.key(), config.verifier_program, ErrorCode::InvalidVerifierProgram);
+            let verification_key = ctx.accounts.verification_key.as_ref().ok_or(ErrorCode::InvalidVerificationKey)?;
+            require_keys_eq!(verification_key.key(), config.verification_key, ErrorCode::InvalidVerificationKey);
+            // a key closed and re-registered at the same address no longer matches
+            check_verification_key(verification_key, &config.verifier_program, &config.verification_key_hash)?;
+            let inputs = public_inputs.to_field_elements().to_vec();
+            match &ctx.accounts.proof_buffer {
+                Some(buffer) => {
+                    let cpi_accounts = verifier::cpi::accounts::VerifyWithVkFromBuffer {
+                        verification_key: verification_key.to_account_info(),
+                        proof_buffer: buffer.to_account_info(),
+                    };
+                    verifier::cpi::verify_with_vk_from_buffer(CpiContext::new(verifier.to_account_info(), cpi_accounts), inputs)?;
+                }
+                None => {
+                    let groth16_proof = Groth16Proof::from_bytes(&proof).map_err(|_| ErrorCode::InvalidProof)?;
+                    let cpi_accounts = verifier::cpi::accounts::VerifyWithVk {
+                        verification_key: verification_key.to_account_info(),
+                    };
+                    verifier::cpi::verify_with_vk(CpiContext::new(verifier.to_account_info(), cpi_accounts), groth16_proof, inputs)?;
+                }
+            }
         } else {
-            // when off or other, require non-empty proof (placeholder)
-            require!(proof.len() > 0, ErrorCode::InvalidProof);
+            return err!(ErrorCode::InvalidProof);
         }
 
-        // Nullifier chunk check and atomic mark
-        let chunk = &mut ctx.accounts.nullifier_chunk;
-        // compute expected chunk index / bit index from nullifier prefix
-        let prefix_bytes: [u8;8] = nullifier[0..8].try_into().unwrap();
-        let prefix = u64::from_le_bytes(prefix_bytes);
-        let chunk_index = prefix / 256u64;
-        let bit_index = (prefix % 256u64) as usize;
-        require!(chunk.index == chunk_index, ErrorCode::NullifierAlreadyUsed); // chunk mismatch treated as used
-        let byte_idx = bit_index / 8;
-        let bit_mask = 1u8 << (bit_index % 8);
-        require!(chunk.bitmap[byte_idx] & bit_mask == 0, ErrorCode::NullifierAlreadyUsed);
-        // mark bit
-        chunk.bitmap[byte_idx] |= bit_mask;
+        // Spend the nullifier; a second spend finds the record already stamped
+        ctx.accounts.nullifier_record.spend(nullifier, Clock::get()?.unix_timestamp, ctx.bumps.nullifier_record)?;
+        let manager = &mut ctx.accounts.nullifier_manager;
+        manager.count = manager.count.checked_add(1).unwrap();
 
         // Transfer tokens from vault to recipient token account
-        let vault_seeds: &[&[u8]] = &[VAULT_SEED, &[ctx.accounts.vault.bump]];
+        let vault_seeds: &[&[u8]] = &[VAULT_SEED, ctx.accounts.vault.mint.as_ref(), &[ctx.accounts.vault.bump]];
         let signer_seeds: &[&[&[u8]]] = &[vault_seeds];
 
         let cpi_accounts = Transfer {
@@ -233,64 +637,80 @@ pub mod payfi {
     }
 
     /// Withdraw executed by a trusted relayer who has validated the proof off-chain.
-    /// Relayer must be in `admin.relayers` and must sign this tx.
-    pub fn withdraw_by_relayer(ctx: Context<WithdrawByRelayer>, nullifier: [u8;32], root: [u8;32], amount: u64, attestation_sig: Vec<u8>, attestation_pubkey: Pubkey, attestation_expiry: u64) -> Result<()> {
-        let admin = &mut ctx.accounts.admin;
+    /// Relayer must have an active `RelayerRecord` and must sign this tx. Above
+    /// `threshold_amount`, co-signers' records are passed as remaining accounts.
+    #[allow(clippy::too_many_arguments)]
+    pub fn withdraw_by_relayer(ctx: Context<WithdrawByRelayer>, nullifier: [u8;32], root: [u8;32], amount: u64, fee: u64, attestation_pubkey: Pubkey, attestation_expiry: u64) -> Result<()> {
+        let config = &ctx.accounts.pool_config;
         // pause check
-        require!(!admin.paused, ErrorCode::ContractPaused);
+        config.require_not_paused(PAUSE_RELAYER_WITHDRAWALS, Clock::get()?.unix_timestamp)?;
 
         // relayer authorization
-        require!(admin.relayers.iter().any(|r| r == &ctx.accounts.relayer.key()), ErrorCode::Unauthorized);
+        require!(ctx.accounts.relayer_record.active, ErrorCode::Unauthorized);
 
         // recipient deny-list check
-        require!(!admin.deny_list.iter().any(|a| a == &ctx.accounts.recipient_token_account.owner), ErrorCode::DenyListBlocked);
+        check_not_denied(&ctx.accounts.deny_list_entry, Clock::get()?.unix_timestamp)?;
+
+        // Verify root is current or still within the recent root history window
+        require!(ctx.accounts.tree_state.is_known_root(&root), ErrorCode::RootMismatch);
 
-        // Verify root
-        require!(ctx.accounts.tree_state.root == root, ErrorCode::RootMismatch);
+        // relayer fee is capped by the pool's and the relayer's max fee (bps of amount)
+        check_relayer_fee(amount, fee, config.max_fee_bps.min(ctx.accounts.relayer_record.fee_bps))?;
 
         // attestation expiry
         let clock = Clock::get()?;
         require!((attestation_expiry as i64) >= clock.unix_timestamp, ErrorCode::AttestationExpired);
 
+        // per-relayer rate limit (count and amount per window)
+        ctx.accounts.relayer_record.record_withdrawal(clock.unix_timestamp, amount)?;
+
         // attestation pubkey must be relayer
         require!(attestation_pubkey == ctx.accounts.relayer.key(), ErrorCode::Unauthorized);
 
-        // build attestation message: nullifier || root || recipient_pubkey || amount || expiry
-        let mut message: Vec<u8> = Vec::new();
-        message.extend_from_slice(&nullifier);
-        message.extend_from_slice(&root);
-        message.extend_from_slice(&ctx.accounts.recipient_token_account.owner.to_bytes());
-        message.extend_from_slice(&amount.to_le_bytes());
-        message.extend_from_slice(&attestation_expiry.to_le_bytes());
-
-        // verify attestation signature length and pubkey length
-        require!(attestation_sig.len() == 64, ErrorCode::InvalidAttestation);
-        require!(attestation_pubkey.to_bytes().len() == 32, ErrorCode::InvalidAttestation);
+        // versioned, domain-separated attestation the relayer(s) must have signed
+        let message = Attestation::new(
+            ctx.accounts.vault.key(),
+            ctx.accounts.vault_token_account.mint,
+            nullifier,
+            root,
+            ctx.accounts.recipient_token_account.key(),
+            amount,
+            fee,
+            attestation_expiry,
+        )
+        .to_message();
 
         // Optionally request more compute units (no-op unless compiled with `compute_budget` feature)
         request_compute_units(200_000)?;
 
-        // verify ed25519 signature via runtime syscall (manual instruction builder)
-        verify_ed25519(&attestation_sig, &attestation_pubkey, &message)?;
-
-
-
+        // Signatures are checked by Ed25519SigVerify instructions placed right before this
+        // one; make sure they cover exactly the expected signers and message
+        if amount > config.threshold_amount {
+            // high-value withdrawal: M-of-N registered relayers, including the submitter
+            let ed25519_data = load_ed25519_instructions(&ctx.accounts.instructions_sysvar)?;
+            check_attestation_versions(&ed25519_data)?;
+            // co-signers come from remaining accounts; the submitter's record is already loaded
+            let mut relayers = active_relayers(ctx.remaining_accounts, &ctx.accounts.vault.mint)?;
+            if !relayers.contains(&ctx.accounts.relayer_record.relayer) {
+                relayers.push(ctx.accounts.relayer_record.relayer);
+            }
+            let signers = attesting_signers(&ed25519_data, &message, &relayers)?;
+            require!(signers.contains(&attestation_pubkey), ErrorCode::InvalidAttestation);
+            require!(signers.len() >= config.attestation_threshold as usize, ErrorCode::AttestationThresholdNotMet);
+        } else {
+            let ed25519_data = load_ed25519_instruction(&ctx.accounts.instructions_sysvar)?;
+            check_attestation_versions(std::slice::from_ref(&ed25519_data))?;
+            verify_ed25519_instruction(&ed25519_data, &attestation_pubkey, &message)?;
+        }
 
-        // Nullifier chunk check and atomic mark
-        let chunk = &mut ctx.accounts.nullifier_chunk;
-        let prefix_bytes: [u8;8] = nullifier[0..8].try_into().unwrap();
-        let prefix = u64::from_le_bytes(prefix_bytes);
-        let chunk_index = prefix / 256u64;
-        let bit_index = (prefix % 256u64) as usize;
-        require!(chunk.index == chunk_index, ErrorCode::NullifierAlreadyUsed); // chunk mismatch treated as used
-        let byte_idx = bit_index / 8;
-        let bit_mask = 1u8 << (bit_index % 8);
-        require!(chunk.bitmap[byte_idx] & bit_mask == 0, ErrorCode::NullifierAlreadyUsed);
-        // mark bit
-        chunk.bitmap[byte_idx] |= bit_mask;
+        // Spend the nullifier; a second spend finds the record already stamped
+        ctx.accounts.nullifier_record.spend(nullifier, Clock::get()?.unix_timestamp, ctx.bumps.nullifier_record)?;
+        let manager = &mut ctx.accounts.nullifier_manager;
+        manager.count = manager.count.checked_add(1).unwrap();
+        ctx.accounts.relayer_record.record_payout(amount, fee);
 
-        // Transfer tokens from vault to recipient token account
-        let vault_seeds: &[&[u8]] = &[VAULT_SEED, &[ctx.accounts.vault.bump]];
+        // Transfer `amount - fee` to the recipient and `fee` to the relayer
+        let vault_seeds: &[&[u8]] = &[VAULT_SEED, ctx.accounts.vault.mint.as_ref(), &[ctx.accounts.vault.bump]];
         let signer_seeds: &[&[&[u8]]] = &[vault_seeds];
 
         let cpi_accounts = Transfer {
@@ -299,53 +719,24 @@ pub mod payfi {
             authority: ctx.accounts.vault.to_account_info(),
         };
         let cpi_program = ctx.accounts.token_program.to_account_info();
-        token::transfer(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds), amount)?;
+        token::transfer(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds), amount - fee)?;
+
+        if fee > 0 {
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.vault_token_account.to_account_info(),
+                to: ctx.accounts.relayer_fee_account.to_account_info(),
+                authority: ctx.accounts.vault.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            token::transfer(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds), fee)?;
+            emit!(FeePaidEvent { nullifier, relayer: ctx.accounts.relayer.key(), amount, fee });
+        }
 
         emit!(WithdrawEvent { nullifier });
         Ok(())
     }
 }
 
-fn verify_ed25519(sig: &[u8], pubkey: &Pubkey, message: &[u8]) -> Result<()> {
-    // Build ed25519 instruction data manually following Solana ed25519 program layout
-    // Layout: u8: signature_count (1)
-    // For each signature: u16 sig_offset, u8 sig_instruction_index, u16 pubkey_offset, u8 pubkey_instruction_index,
-    // u16 message_offset, u16 message_length, u8 message_instruction_index
-
-    require!(sig.len() == 64, ErrorCode::InvalidAttestation);
-    require!(pubkey.to_bytes().len() == 32, ErrorCode::InvalidAttestation);
-
-    let sig_len = sig.len();
-    let pubkey_len = pubkey.to_bytes().len();
-    let message_len = message.len();
-
-    let header_len = 1 + 11 * 1; // 1 signature
-    let sig_offset = header_len as u16;
-    let pubkey_offset = (sig_offset as usize + sig_len) as u16;
-    let message_offset = (pubkey_offset as usize + pubkey_len) as u16;
-
-    let mut data: Vec<u8> = Vec::with_capacity(header_len + sig_len + pubkey_len + message_len);
-    data.push(1u8); // one signature
-    data.extend_from_slice(&sig_offset.to_le_bytes());
-    data.push(0u8); // signature instruction index
-    data.extend_from_slice(&pubkey_offset.to_le_bytes());
-    data.push(0u8); // pubkey instruction index
-    data.extend_from_slice(&message_offset.to_le_bytes());
-    data.extend_from_slice(&(message_len as u16).to_le_bytes());
-    data.push(0u8); // message instruction index
-
-    data.extend_from_slice(sig);
-    data.extend_from_slice(&pubkey.to_bytes());
-    data.extend_from_slice(message);
-
-    // Ed25519 program id (well-known)
-    let ed_prog = Pubkey::from_str("Ed25519SigVerify111111111111111111111111111").map_err(|_| ErrorCode::InvalidAttestation)?;
-    let ix = Instruction::new_with_bytes(ed_prog, &data, vec![]);
-    let res = invoke(&ix, &[]);
-    if res.is_err() { return Err(ErrorCode::InvalidAttestation.into()); }
-    Ok(())
-}
-
 // Compute budget helper (feature-flagged): when compiled with `compute_budget`, this will
 // invoke the built-in ComputeBudget program to request additional compute units before
 // running expensive operations like ed25519 verification.
@@ -371,16 +762,9 @@ fn request_compute_units(_units: u32) -> Result<()> {
 }
 
 #[derive(Accounts)]
-#[instruction(admin: Pubkey, max_chunks: u64)]
 pub struct Initialize<'info> {
-    #[account(init, payer = payer, space = 8 + 32 + (4 + 32 * 10) + 1 + (4 + 64), seeds = [ADMIN_SEED], bump)]
+    #[account(init, payer = payer, space = ADMIN_SPACE, seeds = [ADMIN_SEED], bump)]
     pub admin: Account<'info, Admin>,
-    #[account(init, payer = payer, space = 8 + 32 + 1, seeds = [TREE_STATE_SEED], bump)]
-    pub tree_state: Account<'info, TreeState>,
-    #[account(init, payer = payer, space = 8 + 8 + 8 + 1, seeds = [b"nullifier_manager"], bump)]
-    pub nullifier_manager: Account<'info, NullifierManager>,
-    #[account(init, payer = payer, space = 8 + 32 + 1, seeds = [VAULT_SEED], bump)]
-    pub vault: Account<'info, Vault>,
 
     #[account(mut)]
     pub payer: Signer<'info>,
@@ -388,151 +772,511 @@ pub struct Initialize<'info> {
     pub rent: Sysvar<'info, Rent>,
 }
 
+#[derive(Accounts)]
+#[instruction(mint: Pubkey, root_history_size: u16)]
+pub struct InitializePool<'info> {
+    #[account(seeds = [ADMIN_SEED], bump = admin.bump, has_one = authority)]
+    pub admin: Account<'info, Admin>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(address = mint)]
+    pub mint_account: Account<'info, Mint>,
+    #[account(init, payer = authority, space = 8 + 1 + 32 + 32 + 1, seeds = [VAULT_SEED, mint.as_ref()], bump)]
+    pub vault: Account<'info, Vault>,
+    /// Token account holding the pool's deposits; must be owned by the vault PDA
+    #[account(constraint = vault_token_account.mint == mint @ ErrorCode::InvalidMint, constraint = vault_token_account.owner == vault.key() @ ErrorCode::Unauthorized)]
+    pub vault_token_account: Account<'info, TokenAccount>,
+    #[account(init, payer = authority, space = 8 + 1 + 32 + 8 + 32 * MERKLE_TREE_DEPTH * 2 + (4 + 32 * root_history_size as usize) + 4 + 1, seeds = [TREE_STATE_SEED, mint.as_ref()], bump)]
+    pub tree_state: Box<Account<'info, TreeState>>,
+    #[account(init, payer = authority, space = 8 + 1 + 8 + 1, seeds = [NULLIFIER_MANAGER_SEED, mint.as_ref()], bump)]
+    pub nullifier_manager: Account<'info, NullifierManager>,
+    #[account(init, payer = authority, space = POOL_CONFIG_SPACE, seeds = [POOL_CONFIG_SEED, mint.as_ref()], bump)]
+    pub pool_config: Box<Account<'info, PoolConfig>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateAdmin<'info> {
+    /// CHECK: decoded by `migration::upgrade_admin`, since the stored layout may predate `Admin`
+    #[account(mut, seeds = [ADMIN_SEED], bump, owner = crate::ID)]
+    pub admin: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(Accounts)]
 pub struct Deposit<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
 
-    #[account(mut, constraint = from.owner == user.key())]
+    #[account(mut, constraint = from.owner == user.key(), constraint = from.mint == vault.mint @ ErrorCode::InvalidMint)]
     pub from: Account<'info, TokenAccount>,
 
-    #[account(mut, seeds = [ADMIN_SEED], bump = admin.bump)]
-    pub admin: Account<'info, Admin>,
+    #[account(seeds = [POOL_CONFIG_SEED, vault.mint.as_ref()], bump = pool_config.bump)]
+    pub pool_config: Box<Account<'info, PoolConfig>>,
 
-    /// CHECK: vault PDA account - authority for transfers
-    #[account(mut)]
+    /// CHECK: deny-list PDA for `user`; normally does not exist
+    #[account(seeds = [DENY_LIST_SEED, user.key().as_ref()], bump)]
+    pub deny_list_entry: UncheckedAccount<'info>,
+
+    /// Pool being deposited into
+    #[account(seeds = [VAULT_SEED, vault.mint.as_ref()], bump = vault.bump)]
     pub vault: Account<'info, Vault>,
 
     #[account(mut, address = vault.token_account)]
     pub vault_token_account: Account<'info, TokenAccount>,
 
-    #[account(mut)]
-    pub tree_state: Account<'info, TreeState>,
+    #[account(mut, seeds = [TREE_STATE_SEED, vault.mint.as_ref()], bump = tree_state.bump)]
+    pub tree_state: Box<Account<'info, TreeState>>,
 
     pub token_program: Program<'info, Token>,
 }
 
 #[derive(Accounts)]
+#[instruction(proof: Vec<u8>, nullifier: [u8;32])]
 pub struct Withdraw<'info> {
     /// CHECK: proof verification is done off-chain for now
+    #[account(mut)]
     pub authority: Signer<'info>,
 
 
-    #[account(mut, seeds = [ADMIN_SEED], bump = admin.bump, has_one = authority)]
+    #[account(seeds = [ADMIN_SEED], bump = admin.bump, has_one = authority)]
     pub admin: Account<'info, Admin>,
 
+    #[account(seeds = [POOL_CONFIG_SEED, vault.mint.as_ref()], bump = pool_config.bump)]
+    pub pool_config: Box<Account<'info, PoolConfig>>,
+
     /// CHECK: vault PDA
-    #[account(mut, seeds = [VAULT_SEED], bump = vault.bump)]
+    #[account(mut, seeds = [VAULT_SEED, vault.mint.as_ref()], bump = vault.bump)]
     pub vault: Account<'info, Vault>,
 
     #[account(mut, address = vault.token_account)]
     pub vault_token_account: Account<'info, TokenAccount>,
 
-    #[account(mut)]
+    #[account(mut, constraint = recipient_token_account.mint == vault.mint @ ErrorCode::InvalidMint)]
     pub recipient_token_account: Account<'info, TokenAccount>,
 
-    #[account(mut, seeds = [TREE_STATE_SEED], bump = tree_state.bump)]
-    pub tree_state: Account<'info, TreeState>,
+    /// CHECK: deny-list PDA for the recipient's owner; normally does not exist
+    #[account(seeds = [DENY_LIST_SEED, recipient_token_account.owner.as_ref()], bump)]
+    pub deny_list_entry: UncheckedAccount<'info>,
 
-    /// Nullifier chunk corresponding to the nullifier being spent
-    #[account(mut)]
-    pub nullifier_chunk: Account<'info, NullifierChunk>,
+    #[account(mut, seeds = [TREE_STATE_SEED, vault.mint.as_ref()], bump = tree_state.bump)]
+    pub tree_state: Box<Account<'info, TreeState>>,
 
-    /// CHECK: Verifier program account (for CPI when verifier_mode == 2). Program id only, no account validation.
+    /// One record per spent nullifier in this pool, keyed by the full 32-byte value
+    #[account(init_if_needed, payer = authority, space = 8 + 32 + 8 + 1, seeds = [NULLIFIER_SEED, vault.mint.as_ref(), nullifier.as_ref()], bump)]
+    pub nullifier_record: Account<'info, NullifierRecord>,
+
+    #[account(mut, seeds = [NULLIFIER_MANAGER_SEED, vault.mint.as_ref()], bump = nullifier_manager.bump)]
+    pub nullifier_manager: Account<'info, NullifierManager>,
+
+    /// CHECK: Verifier program account (for CPI when verifier_mode == 2); must equal `pool_config.verifier_program`
     pub verifier_program: UncheckedAccount<'info>,
 
+    /// CHECK: registered verifying key used in mode 2; must equal `pool_config.verification_key` and hash to `pool_config.verification_key_hash`, the verifier checks its seeds
+    pub verification_key: Option<UncheckedAccount<'info>>,
+
+    /// Proof uploaded through the verifier's `write_proof_chunk`, used instead of instruction data
+    pub proof_buffer: Option<Box<Account<'info, ProofBuffer>>>,
+
     pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct SetVerifierMode<'info> {
-    #[account(mut, seeds = [ADMIN_SEED], bump = admin.bump, has_one = authority)]
+#[instruction(relayer: Pubkey)]
+pub struct InitRelayerRecord<'info> {
+    #[account(seeds = [ADMIN_SEED], bump = admin.bump)]
     pub admin: Account<'info, Admin>,
     pub authority: Signer<'info>,
-    /// When using mode=2 (CPI), pass the verifier program account
-    pub verifier_program: Option<UncheckedAccount<'info>>,
+    /// Role assignment of `authority`; not needed when it is the admin authority
+    #[account(seeds = [ROLE_SEED, authority.key().as_ref()], bump = role.bump)]
+    pub role: Option<Account<'info, RoleAssignment>>,
+
+    #[account(seeds = [VAULT_SEED, vault.mint.as_ref()], bump = vault.bump)]
+    pub vault: Account<'info, Vault>,
+    #[account(init, payer = payer, space = 8 + 32 + 32 + 1 + 32 + 2 + 8 * 8 + 8 * 3 + 1, seeds = [RELAYER_STATE_SEED, vault.mint.as_ref(), relayer.as_ref()], bump)]
+    pub relayer_record: Account<'info, RelayerRecord>,
+    #[account(address = vault.token_account)]
+    pub vault_token_account: Account<'info, TokenAccount>,
+    #[account(address = vault_token_account.mint)]
+    pub mint: Account<'info, Mint>,
+    /// Bond escrow, owned by the vault PDA so only this program can move it
+    #[account(init, payer = payer, token::mint = mint, token::authority = vault, seeds = [RELAYER_BOND_SEED, vault.mint.as_ref(), relayer.as_ref()], bump)]
+    pub bond_escrow: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
-#[instruction(index: u64)]
-pub struct InitNullifierChunk<'info> {
-    #[account(init, payer = payer, space = 8 + 8 + 32 + 1, seeds = [NULLIFIER_CHUNK_SEED, &index.to_le_bytes()], bump)]
-    pub chunk: Account<'info, NullifierChunk>,
+pub struct BondRelayer<'info> {
+    pub relayer: Signer<'info>,
+    #[account(mut, seeds = [RELAYER_STATE_SEED, relayer_record.mint.as_ref(), relayer.key().as_ref()], bump = relayer_record.bump)]
+    pub relayer_record: Account<'info, RelayerRecord>,
+    #[account(mut, constraint = relayer_token_account.owner == relayer.key())]
+    pub relayer_token_account: Account<'info, TokenAccount>,
+    #[account(mut, seeds = [RELAYER_BOND_SEED, relayer_record.mint.as_ref(), relayer.key().as_ref()], bump)]
+    pub bond_escrow: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(relayer: Pubkey)]
+pub struct SlashRelayer<'info> {
+    #[account(seeds = [ADMIN_SEED], bump = admin.bump)]
+    pub admin: Account<'info, Admin>,
+    pub authority: Signer<'info>,
+    /// Role assignment of `authority`; not needed when it is the admin authority
+    #[account(seeds = [ROLE_SEED, authority.key().as_ref()], bump = role.bump)]
+    pub role: Option<Account<'info, RoleAssignment>>,
+    #[account(mut, seeds = [POOL_CONFIG_SEED, vault.mint.as_ref()], bump = pool_config.bump)]
+    pub pool_config: Box<Account<'info, PoolConfig>>,
+    #[account(mut, seeds = [RELAYER_STATE_SEED, vault.mint.as_ref(), relayer.as_ref()], bump = relayer_record.bump)]
+    pub relayer_record: Account<'info, RelayerRecord>,
+    #[account(mut, seeds = [RELAYER_BOND_SEED, vault.mint.as_ref(), relayer.as_ref()], bump)]
+    pub bond_escrow: Account<'info, TokenAccount>,
+    #[account(seeds = [VAULT_SEED, vault.mint.as_ref()], bump = vault.bump)]
+    pub vault: Account<'info, Vault>,
+    #[account(mut, address = vault.token_account)]
+    pub vault_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct RequestUnbond<'info> {
+    pub relayer: Signer<'info>,
+    #[account(seeds = [ADMIN_SEED], bump = admin.bump)]
+    pub admin: Account<'info, Admin>,
+    #[account(mut, seeds = [POOL_CONFIG_SEED, relayer_record.mint.as_ref()], bump = pool_config.bump)]
+    pub pool_config: Box<Account<'info, PoolConfig>>,
+    #[account(mut, seeds = [RELAYER_STATE_SEED, relayer_record.mint.as_ref(), relayer.key().as_ref()], bump = relayer_record.bump)]
+    pub relayer_record: Account<'info, RelayerRecord>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawBond<'info> {
+    pub relayer: Signer<'info>,
+    #[account(seeds = [ADMIN_SEED], bump = admin.bump)]
+    pub admin: Account<'info, Admin>,
+    #[account(mut, seeds = [RELAYER_STATE_SEED, vault.mint.as_ref(), relayer.key().as_ref()], bump = relayer_record.bump)]
+    pub relayer_record: Account<'info, RelayerRecord>,
+    #[account(mut, seeds = [RELAYER_BOND_SEED, vault.mint.as_ref(), relayer.key().as_ref()], bump)]
+    pub bond_escrow: Account<'info, TokenAccount>,
+    #[account(seeds = [VAULT_SEED, vault.mint.as_ref()], bump = vault.bump)]
+    pub vault: Account<'info, Vault>,
+    #[account(mut, constraint = relayer_token_account.owner == relayer.key())]
+    pub relayer_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SetBondParams<'info> {
+    #[account(mut, seeds = [ADMIN_SEED], bump = admin.bump)]
+    pub admin: Account<'info, Admin>,
+    pub authority: Signer<'info>,
+    /// Role assignment of `authority`; not needed when it is the admin authority
+    #[account(seeds = [ROLE_SEED, authority.key().as_ref()], bump = role.bump)]
+    pub role: Option<Account<'info, RoleAssignment>>,
+    #[account(mut, seeds = [POOL_CONFIG_SEED, pool_config.mint.as_ref()], bump = pool_config.bump)]
+    pub pool_config: Account<'info, PoolConfig>,
+}
+
+#[derive(Accounts)]
+#[instruction(relayer: Pubkey)]
+pub struct SetRelayerLimits<'info> {
+    #[account(seeds = [ADMIN_SEED], bump = admin.bump)]
+    pub admin: Account<'info, Admin>,
+    pub authority: Signer<'info>,
+    /// Role assignment of `authority`; not needed when it is the admin authority
+    #[account(seeds = [ROLE_SEED, authority.key().as_ref()], bump = role.bump)]
+    pub role: Option<Account<'info, RoleAssignment>>,
+    #[account(mut, seeds = [RELAYER_STATE_SEED, relayer_record.mint.as_ref(), relayer.as_ref()], bump = relayer_record.bump)]
+    pub relayer_record: Account<'info, RelayerRecord>,
+}
+
+#[derive(Accounts)]
+pub struct QueueChange<'info> {
+    #[account(mut, seeds = [ADMIN_SEED], bump = admin.bump)]
+    pub admin: Account<'info, Admin>,
+    /// Admin authority or a holder of the change's role
     #[account(mut)]
-    pub manager: Account<'info, NullifierManager>,
+    pub authority: Signer<'info>,
+    /// Role assignment of `authority`; not needed when it is the admin authority
+    #[account(seeds = [ROLE_SEED, authority.key().as_ref()], bump = role.bump)]
+    pub role: Option<Account<'info, RoleAssignment>>,
+    #[account(init, payer = authority, space = 8 + 8 + ConfigChange::MAX_SPACE + 32 + 8 + 8 + 1, seeds = [PENDING_CHANGE_SEED, admin.next_change_id.to_le_bytes().as_ref()], bump)]
+    pub pending_change: Account<'info, PendingChange>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct ExecuteChange<'info> {
+    #[account(mut, seeds = [ADMIN_SEED], bump = admin.bump)]
+    pub admin: Account<'info, Admin>,
+    pub executor: Signer<'info>,
+    #[account(mut, close = queued_by, seeds = [PENDING_CHANGE_SEED, id.to_le_bytes().as_ref()], bump = pending_change.bump)]
+    pub pending_change: Account<'info, PendingChange>,
+    /// CHECK: receives the closed change's rent
+    #[account(mut, address = pending_change.queued_by)]
+    pub queued_by: UncheckedAccount<'info>,
+    /// Relayer targeted by `AddRelayer`/`RemoveRelayer`
+    #[account(mut, seeds = [RELAYER_STATE_SEED, relayer_record.mint.as_ref(), relayer_record.relayer.as_ref()], bump = relayer_record.bump)]
+    pub relayer_record: Option<Account<'info, RelayerRecord>>,
+    /// CHECK: program pinned by `VerifierProgram`; checked against the change and for executability
+    pub verifier_program: Option<UncheckedAccount<'info>>,
+    /// CHECK: verifying key pinned by `VerifierProgram`; checked against the change and owned by the verifier
+    pub verification_key: Option<UncheckedAccount<'info>>,
+    /// Pool targeted by the change
+    #[account(mut, seeds = [POOL_CONFIG_SEED, pool_config.mint.as_ref()], bump = pool_config.bump)]
+    pub pool_config: Option<Box<Account<'info, PoolConfig>>>,
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct CancelChange<'info> {
+    #[account(seeds = [ADMIN_SEED], bump = admin.bump)]
+    pub admin: Account<'info, Admin>,
+    /// Admin authority or a holder of the change's role
+    pub authority: Signer<'info>,
+    /// Role assignment of `authority`; not needed when it is the admin authority
+    #[account(seeds = [ROLE_SEED, authority.key().as_ref()], bump = role.bump)]
+    pub role: Option<Account<'info, RoleAssignment>>,
+    #[account(mut, close = queued_by, seeds = [PENDING_CHANGE_SEED, id.to_le_bytes().as_ref()], bump = pending_change.bump)]
+    pub pending_change: Account<'info, PendingChange>,
+    /// CHECK: receives the closed change's rent
+    #[account(mut, address = pending_change.queued_by)]
+    pub queued_by: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(holder: Pubkey)]
+pub struct GrantRole<'info> {
+    #[account(seeds = [ADMIN_SEED], bump = admin.bump, has_one = authority)]
+    pub admin: Account<'info, Admin>,
     #[account(mut)]
-    pub payer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(init_if_needed, payer = authority, space = 8 + 32 + 1 + 1, seeds = [ROLE_SEED, holder.as_ref()], bump)]
+    pub role: Account<'info, RoleAssignment>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct UpdateRoot<'info> {
-    #[account(mut, seeds = [ADMIN_SEED], bump = admin.bump, has_one = authority)]
+#[instruction(holder: Pubkey)]
+pub struct RevokeRole<'info> {
+    #[account(seeds = [ADMIN_SEED], bump = admin.bump, has_one = authority)]
     pub admin: Account<'info, Admin>,
     pub authority: Signer<'info>,
-    #[account(mut, seeds = [TREE_STATE_SEED], bump = tree_state.bump)]
-    pub tree_state: Account<'info, TreeState>,
+    #[account(mut, seeds = [ROLE_SEED, holder.as_ref()], bump = role.bump)]
+    pub role: Account<'info, RoleAssignment>,
 }
 
 #[derive(Accounts)]
-pub struct ModifyDenyList<'info> {
+pub struct CancelAuthorityTransfer<'info> {
     #[account(mut, seeds = [ADMIN_SEED], bump = admin.bump, has_one = authority)]
     pub admin: Account<'info, Admin>,
     pub authority: Signer<'info>,
 }
 
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(mut, seeds = [ADMIN_SEED], bump = admin.bump)]
+    pub admin: Account<'info, Admin>,
+    pub new_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetDenyListMode<'info> {
+    #[account(seeds = [ADMIN_SEED], bump = admin.bump)]
+    pub admin: Account<'info, Admin>,
+    pub authority: Signer<'info>,
+    /// Role assignment of `authority`; not needed when it is the admin authority
+    #[account(seeds = [ROLE_SEED, authority.key().as_ref()], bump = role.bump)]
+    pub role: Option<Account<'info, RoleAssignment>>,
+    #[account(mut, seeds = [POOL_CONFIG_SEED, pool_config.mint.as_ref()], bump = pool_config.bump)]
+    pub pool_config: Account<'info, PoolConfig>,
+}
+
+#[derive(Accounts)]
+#[instruction(addr: Pubkey)]
+pub struct AddToDenyList<'info> {
+    #[account(seeds = [ADMIN_SEED], bump = admin.bump)]
+    pub admin: Account<'info, Admin>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// Role assignment of `authority`; not needed when it is the admin authority
+    #[account(seeds = [ROLE_SEED, authority.key().as_ref()], bump = role.bump)]
+    pub role: Option<Account<'info, RoleAssignment>>,
+    #[account(init, payer = authority, space = DENY_LIST_ENTRY_SPACE, seeds = [DENY_LIST_SEED, addr.as_ref()], bump)]
+    pub deny_list_entry: Account<'info, DenyListEntry>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(addr: Pubkey)]
+pub struct RemoveFromDenyList<'info> {
+    #[account(seeds = [ADMIN_SEED], bump = admin.bump)]
+    pub admin: Account<'info, Admin>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// Role assignment of `authority`; not needed when it is the admin authority
+    #[account(seeds = [ROLE_SEED, authority.key().as_ref()], bump = role.bump)]
+    pub role: Option<Account<'info, RoleAssignment>>,
+    #[account(mut, close = authority, seeds = [DENY_LIST_SEED, addr.as_ref()], bump = deny_list_entry.bump)]
+    pub deny_list_entry: Account<'info, DenyListEntry>,
+}
+
 #[derive(Accounts)]
 pub struct SetPause<'info> {
-    #[account(mut, seeds = [ADMIN_SEED], bump = admin.bump, has_one = authority)]
+    #[account(seeds = [ADMIN_SEED], bump = admin.bump)]
     pub admin: Account<'info, Admin>,
     pub authority: Signer<'info>,
+    /// Role assignment of `authority`; not needed when it is the admin authority
+    #[account(seeds = [ROLE_SEED, authority.key().as_ref()], bump = role.bump)]
+    pub role: Option<Account<'info, RoleAssignment>>,
+    #[account(mut, seeds = [POOL_CONFIG_SEED, pool_config.mint.as_ref()], bump = pool_config.bump)]
+    pub pool_config: Account<'info, PoolConfig>,
 }
 
 #[derive(Accounts)]
-pub struct ModifyRelayer<'info> {
-    #[account(mut, seeds = [ADMIN_SEED], bump = admin.bump, has_one = authority)]
+pub struct SetAttestationThreshold<'info> {
+    #[account(seeds = [ADMIN_SEED], bump = admin.bump)]
+    pub admin: Account<'info, Admin>,
+    pub authority: Signer<'info>,
+    /// Role assignment of `authority`; not needed when it is the admin authority
+    #[account(seeds = [ROLE_SEED, authority.key().as_ref()], bump = role.bump)]
+    pub role: Option<Account<'info, RoleAssignment>>,
+    #[account(mut, seeds = [POOL_CONFIG_SEED, pool_config.mint.as_ref()], bump = pool_config.bump)]
+    pub pool_config: Account<'info, PoolConfig>,
+}
+
+#[derive(Accounts)]
+pub struct SetMaxFeeBps<'info> {
+    #[account(seeds = [ADMIN_SEED], bump = admin.bump)]
     pub admin: Account<'info, Admin>,
     pub authority: Signer<'info>,
+    /// Role assignment of `authority`; not needed when it is the admin authority
+    #[account(seeds = [ROLE_SEED, authority.key().as_ref()], bump = role.bump)]
+    pub role: Option<Account<'info, RoleAssignment>>,
+    #[account(mut, seeds = [POOL_CONFIG_SEED, pool_config.mint.as_ref()], bump = pool_config.bump)]
+    pub pool_config: Account<'info, PoolConfig>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRelayerProfile<'info> {
+    pub relayer: Signer<'info>,
+    #[account(mut, seeds = [RELAYER_STATE_SEED, relayer_record.mint.as_ref(), relayer.key().as_ref()], bump = relayer_record.bump)]
+    pub relayer_record: Account<'info, RelayerRecord>,
 }
 
 #[derive(Accounts)]
+#[instruction(nullifier: [u8;32])]
 pub struct WithdrawByRelayer<'info> {
+    #[account(mut)]
     pub relayer: Signer<'info>,
 
-    #[account(mut, seeds = [ADMIN_SEED], bump = admin.bump)]
-    pub admin: Account<'info, Admin>,
+    #[account(seeds = [POOL_CONFIG_SEED, vault.mint.as_ref()], bump = pool_config.bump)]
+    pub pool_config: Box<Account<'info, PoolConfig>>,
 
     /// CHECK: vault PDA
-    #[account(mut, seeds = [VAULT_SEED], bump = vault.bump)]
+    #[account(mut, seeds = [VAULT_SEED, vault.mint.as_ref()], bump = vault.bump)]
     pub vault: Account<'info, Vault>,
 
     #[account(mut, address = vault.token_account)]
     pub vault_token_account: Account<'info, TokenAccount>,
 
-    #[account(mut)]
+    #[account(mut, constraint = recipient_token_account.mint == vault.mint @ ErrorCode::InvalidMint)]
     pub recipient_token_account: Account<'info, TokenAccount>,
 
-    #[account(mut, seeds = [TREE_STATE_SEED], bump = tree_state.bump)]
-    pub tree_state: Account<'info, TreeState>,
+    /// CHECK: deny-list PDA for the recipient's owner; normally does not exist
+    #[account(seeds = [DENY_LIST_SEED, recipient_token_account.owner.as_ref()], bump)]
+    pub deny_list_entry: UncheckedAccount<'info>,
 
-    #[account(mut)]
-    pub nullifier_chunk: Account<'info, NullifierChunk>,
+    /// Relayer's token account receiving `fee`
+    #[account(mut, constraint = relayer_fee_account.owner == relayer.key() @ ErrorCode::Unauthorized, constraint = relayer_fee_account.mint == vault.mint @ ErrorCode::InvalidMint)]
+    pub relayer_fee_account: Account<'info, TokenAccount>,
+
+    #[account(mut, seeds = [TREE_STATE_SEED, vault.mint.as_ref()], bump = tree_state.bump)]
+    pub tree_state: Box<Account<'info, TreeState>>,
+
+    /// One record per spent nullifier in this pool, keyed by the full 32-byte value
+    #[account(init_if_needed, payer = relayer, space = 8 + 32 + 8 + 1, seeds = [NULLIFIER_SEED, vault.mint.as_ref(), nullifier.as_ref()], bump)]
+    pub nullifier_record: Account<'info, NullifierRecord>,
+
+    #[account(mut, seeds = [NULLIFIER_MANAGER_SEED, vault.mint.as_ref()], bump = nullifier_manager.bump)]
+    pub nullifier_manager: Account<'info, NullifierManager>,
 
-    #[account(mut, seeds = [RELAYER_STATE_SEED, relayer.key().as_ref()], bump = relayer_state.bump)]
-    pub relayer_state: Account<'info, RelayerState>,
+    #[account(mut, seeds = [RELAYER_STATE_SEED, vault.mint.as_ref(), relayer.key().as_ref()], bump = relayer_record.bump)]
+    pub relayer_record: Account<'info, RelayerRecord>,
+
+    /// CHECK: Instructions sysvar, read to find the Ed25519SigVerify instruction
+    #[account(address = solana_instructions_sysvar::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
 
     pub token_program: Program<'info, Token>,
-} 
+    pub system_program: Program<'info, System>,
+}
 
+/// One shielded pool per token mint, at `[VAULT_SEED, mint]`. Its tree and nullifier
+/// manager live at `[TREE_STATE_SEED, mint]` and `[NULLIFIER_MANAGER_SEED, mint]`.
 #[account]
 pub struct Vault {
+    /// Layout version, `migration::VAULT_VERSION`
+    pub version: u8,
+    pub mint: Pubkey,
     pub token_account: Pubkey,
     pub bump: u8,
 }
 
+/// Settings of one pool, at `[POOL_CONFIG_SEED, mint]`
+#[account]
+pub struct PoolConfig {
+    /// Layout version, `migration::POOL_CONFIG_VERSION`
+    pub version: u8,
+    pub mint: Pubkey,
+    /// `DENY_LIST_MODE_PDA` or `DENY_LIST_MODE_SANCTIONS_ROOT`
+    pub deny_list_mode: u8,
+    /// Root of the sorted sanctions list, used in `DENY_LIST_MODE_SANCTIONS_ROOT`
+    pub sanctions_root: [u8;32],
+    pub verifier_mode: u8,
+    pub verifier_magic: Vec<u8>,
+    /// Verifier program invoked in mode 2, pinned by `ConfigChange::VerifierProgram`
+    pub verifier_program: Pubkey,
+    /// `VerificationKey` account of `verifier_program` that mode 2 proofs are checked against
+    pub verification_key: Pubkey,
+    /// `verification_key::verification_key_hash` of that account's data when it was pinned
+    pub verification_key_hash: [u8;32],
+    /// Bitfield of `pause::PAUSE_*`
+    pub pause_flags: u8,
+    /// Unix timestamp the pause lifts at; 0 = until `set_pause` clears it
+    pub paused_until: i64,
+    /// Distinct relayer attestations required above `threshold_amount`
+    pub attestation_threshold: u8,
+    pub threshold_amount: u64,
+    /// Upper bound on relayer fees, in basis points of the withdrawn amount
+    pub max_fee_bps: u16,
+    /// Number of active `RelayerRecord`s in this pool
+    pub relayer_count: u32,
+    /// Bond a relayer must hold before `ConfigChange::AddRelayer` executes
+    pub min_relayer_bond: u64,
+    pub bump: u8,
+}
+
 #[account]
 pub struct TreeState {
+    /// Layout version, `migration::TREE_STATE_VERSION`
+    pub version: u8,
     pub root: [u8;32],
+    pub next_index: u64,
+    pub filled_subtrees: [[u8;32]; MERKLE_TREE_DEPTH],
+    pub zeros: [[u8;32]; MERKLE_TREE_DEPTH],
+    pub roots: Vec<[u8;32]>, // ring buffer of recent roots, length fixed at initialize
+    pub current_root_index: u32,
     pub bump: u8,
 }
 
@@ -545,41 +1289,131 @@ pub struct NullifierSet {
 
 #[account]
 pub struct NullifierManager {
+    /// Layout version, `migration::NULLIFIER_MANAGER_VERSION`
+    pub version: u8,
+    /// Number of nullifiers spent
     pub count: u64,
-    pub max_chunks: u64,
     pub bump: u8,
 }
 
+/// Registry entry for one relayer in one pool, at `[RELAYER_STATE_SEED, mint, relayer]`;
+/// its bond escrow is at `[RELAYER_BOND_SEED, mint, relayer]`
 #[account]
-pub struct RelayerState {
+pub struct RelayerRecord {
+    pub relayer: Pubkey,
+    pub mint: Pubkey,
+    /// Set by `ConfigChange::AddRelayer`; only active relayers may relay or attest
+    pub active: bool,
+    /// SHA-256 of the relayer's metadata URL
+    pub metadata_url_hash: [u8;32],
+    /// Most the relayer charges, in basis points of the withdrawn amount
+    pub fee_bps: u16,
     pub window_start: u64,
+    /// Withdrawals in the current window
     pub count: u64,
     pub limit: u64,
     pub window_seconds: u64,
+    /// Tokens withdrawn in the current window
+    pub amount: u64,
+    pub amount_limit: u64,
+    /// Tokens held in the relayer's bond escrow
+    pub bond: u64,
+    /// When the bond can be withdrawn, fixed by `request_unbond`; 0 if the relayer is not unbonding
+    pub unbond_ready_at: u64,
+    /// Lifetime relayed withdrawals, volume and fees earned
+    pub total_withdrawals: u64,
+    pub total_volume: u64,
+    pub total_fees: u64,
+    pub bump: u8,
+}
+
+/// Queued configuration change, at `[PENDING_CHANGE_SEED, id]`
+#[account]
+pub struct PendingChange {
+    pub id: u64,
+    pub change: ConfigChange,
+    pub queued_by: Pubkey,
+    pub queued_at: i64,
+    /// Earliest unix timestamp `execute_change` accepts
+    pub eta: i64,
+    pub bump: u8,
+}
+
+/// Roles held by one key, at `[ROLE_SEED, holder]`
+#[account]
+pub struct RoleAssignment {
+    pub holder: Pubkey,
+    /// Bitmask of `roles::ROLE_*`
+    pub roles: u8,
     pub bump: u8,
 }
 
+/// Blocks one address, at `[DENY_LIST_SEED, address]`
 #[account]
-pub struct NullifierChunk {
-    pub index: u64,
-    pub bitmap: [u8;32], // 256 bits per chunk
+pub struct DenyListEntry {
+    pub address: Pubkey,
+    /// Compliance reason code
+    pub reason: u16,
+    pub added_by: Pubkey,
+    pub added_at: i64,
+    /// Unix timestamp the entry stops applying at; 0 = never
+    pub expires_at: i64,
+    pub bump: u8,
+}
+
+/// Marks a nullifier as spent; exists iff the nullifier has been used
+#[account]
+pub struct NullifierRecord {
+    pub nullifier: [u8;32],
+    pub spent_at: i64,
     pub bump: u8,
 }
 
+/// Program-wide authority and governance, at `[ADMIN_SEED]`; per-pool settings live in
+/// `PoolConfig`
 #[account]
 pub struct Admin {
+    /// Layout version, `migration::ADMIN_VERSION`
+    pub version: u8,
     pub authority: Pubkey,
-    pub deny_list: Vec<Pubkey>,
-    pub relayers: Vec<Pubkey>,
-    pub verifier_mode: u8,
-    pub verifier_magic: Vec<u8>,
-    pub paused: bool,
+    /// Proposed through `ConfigChange::ProposeAuthority`, becomes `authority` on `accept_authority`
+    pub pending_authority: Option<Pubkey>,
+    /// Seconds between `request_unbond` and `withdraw_bond`, at least `bond::MIN_UNBONDING_PERIOD`
+    pub unbonding_period: u64,
+    /// Seconds a queued `ConfigChange` waits before it can be executed
+    pub timelock_delay: u64,
+    /// Id of the next `PendingChange`
+    pub next_change_id: u64,
     pub bump: u8,
 }
 
+#[event]
+pub struct AdminMigratedEvent {
+    pub from_version: u8,
+    pub to_version: u8,
+    /// Relayers the old layout held inline; re-add them through the relayer registry
+    pub legacy_relayers: Vec<Pubkey>,
+    /// Addresses the old layout blocked inline, recreated as `DenyListEntry` PDAs
+    pub legacy_deny_list: Vec<Pubkey>,
+    /// Pool settings the old layout kept in the admin account; re-apply them to the
+    /// legacy mint's `PoolConfig`
+    pub legacy_paused: bool,
+    pub legacy_verifier_mode: u8,
+    pub legacy_verifier_magic: Vec<u8>,
+}
+
+#[event]
+pub struct PoolInitializedEvent {
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub token_account: Pubkey,
+}
+
 #[event]
 pub struct EncryptedNoteEvent {
     pub commitment: [u8;32],
+    pub leaf_index: u64,
+    pub root: [u8;32],
     pub encrypted: Vec<u8>,
 }
 
@@ -588,9 +1422,100 @@ pub struct WithdrawEvent {
     pub nullifier: [u8;32],
 }
 
+#[event]
+pub struct PauseChangedEvent {
+    pub mint: Pubkey,
+    pub flags: u8,
+    pub paused_until: i64,
+    pub changed_by: Pubkey,
+}
+
+#[event]
+pub struct ChangeQueuedEvent {
+    pub id: u64,
+    pub change: ConfigChange,
+    pub queued_by: Pubkey,
+    pub eta: i64,
+}
+
+#[event]
+pub struct ChangeExecutedEvent {
+    pub id: u64,
+    pub executed_by: Pubkey,
+}
+
+#[event]
+pub struct ChangeCancelledEvent {
+    pub id: u64,
+    pub cancelled_by: Pubkey,
+}
+
+#[event]
+pub struct RolesChangedEvent {
+    pub holder: Pubkey,
+    /// Roles held after the change
+    pub roles: u8,
+}
+
+#[event]
+pub struct AuthorityProposedEvent {
+    pub authority: Pubkey,
+    pub proposed: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferredEvent {
+    pub previous: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferCancelledEvent {
+    pub authority: Pubkey,
+    pub cancelled: Pubkey,
+}
+
+#[event]
+pub struct DenyListAddedEvent {
+    pub address: Pubkey,
+    pub reason: u16,
+    pub added_by: Pubkey,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct DenyListRemovedEvent {
+    pub address: Pubkey,
+    pub removed_by: Pubkey,
+}
+
+#[event]
+pub struct SanctionsRootUpdatedEvent {
+    pub mint: Pubkey,
+    pub root: [u8;32],
+    pub updated_by: Pubkey,
+}
+
+#[event]
+pub struct RelayerSlashedEvent {
+    pub mint: Pubkey,
+    pub relayer: Pubkey,
+    pub amount: u64,
+    /// Bond left after the slash
+    pub remaining: u64,
+}
+
+#[event]
+pub struct FeePaidEvent {
+    pub nullifier: [u8;32],
+    pub relayer: Pubkey,
+    pub amount: u64,
+    pub fee: u64,
+}
+
 #[error_code]
 pub enum ErrorCode {
-    #[msg("Provided root does not match the on-chain root")]
+    #[msg("Provided root is not a known recent root")]
     RootMismatch,
     #[msg("Nullifier already used")]
     NullifierAlreadyUsed,
@@ -610,6 +1535,62 @@ pub enum ErrorCode {
     RelayerRateLimited,
     #[msg("Compute budget request failed")]
     ComputeBudgetRequestFailed,
-    #[msg("Nullifier chunk limit reached")]
-    ChunkLimitReached,
+    #[msg("Merkle tree is full")]
+    MerkleTreeFull,
+    #[msg("Invalid root history size")]
+    InvalidRootHistorySize,
+    #[msg("Verifier program does not match the configured verifier")]
+    InvalidVerifierProgram,
+    #[msg("Invalid relayer limits")]
+    InvalidRelayerLimits,
+    #[msg("Invalid attestation threshold")]
+    InvalidAttestationThreshold,
+    #[msg("Not enough relayer attestations")]
+    AttestationThresholdNotMet,
+    #[msg("Relayer attested more than once")]
+    DuplicateAttestation,
+    #[msg("Unsupported attestation version")]
+    UnsupportedAttestationVersion,
+    #[msg("Relayer fee exceeds the pool maximum")]
+    FeeTooHigh,
+    #[msg("Relayer bond is insufficient")]
+    InsufficientRelayerBond,
+    #[msg("Relayer is unbonding")]
+    RelayerUnbonding,
+    #[msg("Unbonding period has not elapsed")]
+    UnbondNotReady,
+    #[msg("Deny-list expiry must be in the future")]
+    InvalidDenyListExpiry,
+    #[msg("Invalid deny-list mode")]
+    InvalidDenyListMode,
+    #[msg("Invalid sanctions non-membership proof")]
+    InvalidSanctionsProof,
+    #[msg("Invalid authority")]
+    InvalidAuthority,
+    #[msg("No authority transfer is pending")]
+    NoPendingAuthority,
+    #[msg("Invalid role")]
+    InvalidRole,
+    #[msg("Invalid configuration change")]
+    InvalidConfigChange,
+    #[msg("Timelock delay has not elapsed")]
+    TimelockNotElapsed,
+    #[msg("Invalid pause flags or auto-unpause time")]
+    InvalidPauseFlags,
+    #[msg("Account layout version is not supported")]
+    UnsupportedAccountVersion,
+    #[msg("Account is already at the current version")]
+    AlreadyMigrated,
+    #[msg("Token account mint does not match the pool")]
+    InvalidMint,
+    #[msg("Verification key does not match the pinned key")]
+    InvalidVerificationKey,
+    #[msg("Unbonding period is below the minimum")]
+    InvalidUnbondingPeriod,
+    #[msg("Accounts do not match the legacy deny list")]
+    LegacyDenyListMismatch,
+    #[msg("Pool config does not match the change")]
+    InvalidPoolConfig,
+    #[msg("Pool has no verifier configured")]
+    VerifierNotConfigured,
 }
@@ -0,0 +1,107 @@
+use anchor_lang::prelude::*;
+use crate::public_inputs::hash_to_field;
+use crate::{ErrorCode, TreeState};
+
+/// Depth of the on-chain commitment tree (2^20 = ~1M notes per pool)
+pub const MERKLE_TREE_DEPTH: usize = 20;
+
+/// Upper bound on the root history ring buffer configured at `initialize`
+pub const MAX_ROOT_HISTORY_SIZE: u16 = 100;
+
+/// Hash two child nodes into their parent.
+///
+/// SHA-256 (cheap on-chain via the `sol_sha256` syscall) reduced with `hash_to_field`,
+/// so every node is a canonical BN254 field element and can be used directly as a
+/// circuit public input.
+pub fn hash_pair(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    hash_to_field(&[left, right])
+}
+
+/// Roots of empty subtrees: `zeros[0]` is the empty leaf, `zeros[i]` the root of an
+/// empty subtree of height `i`.
+pub fn zero_hashes() -> [[u8; 32]; MERKLE_TREE_DEPTH] {
+    let mut zeros = [[0u8; 32]; MERKLE_TREE_DEPTH];
+    for i in 1..MERKLE_TREE_DEPTH {
+        zeros[i] = hash_pair(&zeros[i - 1], &zeros[i - 1]);
+    }
+    zeros
+}
+
+/// Root of a tree with no leaves
+pub fn empty_root() -> [u8; 32] {
+    let zeros = zero_hashes();
+    let top = &zeros[MERKLE_TREE_DEPTH - 1];
+    hash_pair(top, top)
+}
+
+impl TreeState {
+    /// Reset to an empty tree keeping the last `root_history_size` roots
+    pub fn init_tree(&mut self, root_history_size: usize) {
+        self.zeros = zero_hashes();
+        self.filled_subtrees = self.zeros;
+        self.next_index = 0;
+        self.root = empty_root();
+        self.roots = vec![[0u8; 32]; root_history_size];
+        self.roots[0] = self.root;
+        self.current_root_index = 0;
+    }
+
+    /// Make `root` the current root and record it in the history ring buffer,
+    /// evicting the oldest entry once the buffer is full.
+    fn push_root(&mut self, root: [u8; 32]) {
+        self.root = root;
+        if self.roots.is_empty() {
+            return;
+        }
+        let next = (self.current_root_index as usize + 1) % self.roots.len();
+        self.roots[next] = root;
+        self.current_root_index = next as u32;
+    }
+
+    /// True if `root` is the current root or still within the history window
+    pub fn is_known_root(&self, root: &[u8; 32]) -> bool {
+        if *root == [0u8; 32] {
+            // unused ring slots are zeroed; never accept them
+            return false;
+        }
+        *root == self.root || self.roots.iter().any(|r| r == root)
+    }
+
+    /// Append a leaf and recompute the root. Returns the index of the inserted leaf.
+    pub fn append_leaf(&mut self, leaf: [u8; 32]) -> Result<u64> {
+        let leaf_index = self.next_index;
+        require!(leaf_index < (1u64 << MERKLE_TREE_DEPTH), ErrorCode::MerkleTreeFull);
+
+        let mut current_index = leaf_index;
+        let mut current = leaf;
+        for level in 0..MERKLE_TREE_DEPTH {
+            let (left, right) = if current_index % 2 == 0 {
+                // left child: remember it, pair with the empty subtree on the right
+                self.filled_subtrees[level] = current;
+                (current, self.zeros[level])
+            } else {
+                (self.filled_subtrees[level], current)
+            };
+            current = hash_pair(&left, &right);
+            current_index /= 2;
+        }
+
+        self.push_root(current);
+        self.next_index = leaf_index + 1;
+        Ok(leaf_index)
+    }
+}
+
+/// Recompute a root from a leaf and its authentication path (client-side helper).
+/// Bit `i` of `index` selects whether the node at level `i` is a right child.
+pub fn compute_root(leaf: [u8; 32], index: u64, path: &[[u8; 32]; MERKLE_TREE_DEPTH]) -> [u8; 32] {
+    let mut current = leaf;
+    for (level, sibling) in path.iter().enumerate() {
+        current = if (index >> level) & 1 == 0 {
+            hash_pair(&current, sibling)
+        } else {
+            hash_pair(sibling, &current)
+        };
+    }
+    current
+}
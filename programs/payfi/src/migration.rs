@@ -0,0 +1,149 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Allocate, Assign, CreateAccount, Transfer};
+use anchor_lang::Discriminator;
+
+use crate::bond::DEFAULT_UNBONDING_PERIOD;
+use crate::deny_list::DENY_LIST_ENTRY_SPACE;
+use crate::timelock::MAX_VERIFIER_MAGIC_LEN;
+use crate::{Admin, DenyListEntry, ErrorCode, DENY_LIST_SEED};
+
+/// Layouts written by `initialize`; bump when fields change and teach `upgrade_admin`
+/// the previous one
+pub const ADMIN_VERSION: u8 = 1;
+pub const TREE_STATE_VERSION: u8 = 1;
+pub const VAULT_VERSION: u8 = 1;
+pub const NULLIFIER_MANAGER_VERSION: u8 = 1;
+pub const POOL_CONFIG_VERSION: u8 = 1;
+
+/// Space of an `Admin` account at `ADMIN_VERSION`
+pub const ADMIN_SPACE: usize = 8 + 1 + 32 + (1 + 32) + 8 + 8 + 8 + 1;
+
+/// Space of a `PoolConfig` account at `POOL_CONFIG_VERSION`
+pub const POOL_CONFIG_SPACE: usize = 8 + 1 + 32 + 1 + 32 + 1 + (4 + MAX_VERIFIER_MAGIC_LEN) + 32 + 32 + 32 + 1 + 8 + 1 + 8 + 2 + 4 + 8 + 1;
+
+/// Space the unversioned program allocated for `Admin`. No versioned layout has this
+/// size, which is how `upgrade_admin` tells the two apart.
+pub const LEGACY_ADMIN_V0_SPACE: usize = 8 + 32 + (4 + 32 * 10) + 1 + (4 + 64);
+
+/// `Admin` as stored by the program before accounts carried a version
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LegacyAdminV0 {
+    pub authority: Pubkey,
+    pub deny_list: Vec<Pubkey>,
+    pub relayers: Vec<Pubkey>,
+    pub verifier_mode: u8,
+    pub verifier_magic: Vec<u8>,
+    pub paused: bool,
+    pub bump: u8,
+}
+
+/// Result of decoding an `Admin` account in any known layout
+#[derive(Clone)]
+pub struct AdminMigration {
+    /// Version found in the account; 0 for the unversioned layout
+    pub from_version: u8,
+    pub admin: Admin,
+    /// Relayers the old layout stored inline. Registration now needs a bond, so they
+    /// have to be re-added through the registry.
+    pub legacy_relayers: Vec<Pubkey>,
+    /// Addresses the old layout blocked inline; `restore_deny_list` recreates them as PDAs
+    pub legacy_deny_list: Vec<Pubkey>,
+    /// Pool settings the old layout kept in `Admin`. `PoolConfig` is per mint and only
+    /// exists once `initialize_pool` runs, so the authority re-applies these to the legacy
+    /// mint's pool.
+    pub legacy_paused: bool,
+    pub legacy_verifier_mode: u8,
+    pub legacy_verifier_magic: Vec<u8>,
+}
+
+impl LegacyAdminV0 {
+    /// The program-wide part of the configuration in the current layout. The pause and
+    /// verifier settings are per pool now and come back through `AdminMigration`. The
+    /// timelock starts at 0, which keeps changes as immediate as they were until the
+    /// authority raises it.
+    pub fn upgrade(&self) -> Admin {
+        Admin {
+            version: ADMIN_VERSION,
+            authority: self.authority,
+            pending_authority: None,
+            unbonding_period: DEFAULT_UNBONDING_PERIOD,
+            timelock_delay: 0,
+            next_change_id: 0,
+            bump: self.bump,
+        }
+    }
+}
+
+/// Decode raw `Admin` account data and upgrade it to `ADMIN_VERSION`. Accounts already
+/// at the current version are rejected so a migration is never replayed.
+pub fn upgrade_admin(data: &[u8]) -> Result<AdminMigration> {
+    require!(data.len() >= 8 && data[..8] == *Admin::DISCRIMINATOR, ErrorCode::UnsupportedAccountVersion);
+
+    if data.len() == LEGACY_ADMIN_V0_SPACE {
+        let legacy = LegacyAdminV0::deserialize(&mut &data[8..]).map_err(|_| ErrorCode::UnsupportedAccountVersion)?;
+        return Ok(AdminMigration {
+            from_version: 0,
+            admin: legacy.upgrade(),
+            legacy_relayers: legacy.relayers,
+            legacy_deny_list: legacy.deny_list,
+            legacy_paused: legacy.paused,
+            legacy_verifier_mode: legacy.verifier_mode,
+            legacy_verifier_magic: legacy.verifier_magic,
+        });
+    }
+
+    match data.get(8) {
+        Some(&ADMIN_VERSION) => err!(ErrorCode::AlreadyMigrated),
+        _ => err!(ErrorCode::UnsupportedAccountVersion),
+    }
+}
+
+/// Recreate the legacy inline deny list as `DenyListEntry` PDAs paid for by `payer`.
+/// `entries` must be the `[DENY_LIST_SEED, address]` accounts of `deny_list`, in order,
+/// so no blocked address is dropped by the migration.
+pub fn restore_deny_list<'info>(
+    deny_list: &[Pubkey],
+    entries: &[AccountInfo<'info>],
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    now: i64,
+) -> Result<()> {
+    require!(entries.len() == deny_list.len(), ErrorCode::LegacyDenyListMismatch);
+    let rent = Rent::get()?.minimum_balance(DENY_LIST_ENTRY_SPACE);
+
+    for (address, info) in deny_list.iter().zip(entries) {
+        let (expected, bump) = Pubkey::find_program_address(&[DENY_LIST_SEED, address.as_ref()], &crate::ID);
+        require_keys_eq!(info.key(), expected, ErrorCode::LegacyDenyListMismatch);
+        require!(info.data_is_empty() && info.owner == &system_program::ID, ErrorCode::LegacyDenyListMismatch);
+
+        let seeds: &[&[u8]] = &[DENY_LIST_SEED, address.as_ref(), &[bump]];
+        let signer_seeds: &[&[&[u8]]] = &[seeds];
+        // same steps as Anchor's `init`, so lamports sent to the address beforehand cannot block it
+        if info.lamports() == 0 {
+            let cpi_accounts = CreateAccount { from: payer.clone(), to: info.clone() };
+            let cpi_ctx = CpiContext::new_with_signer(system_program.clone(), cpi_accounts, signer_seeds);
+            system_program::create_account(cpi_ctx, rent, DENY_LIST_ENTRY_SPACE as u64, &crate::ID)?;
+        } else {
+            let top_up = rent.saturating_sub(info.lamports());
+            if top_up > 0 {
+                let cpi_accounts = Transfer { from: payer.clone(), to: info.clone() };
+                system_program::transfer(CpiContext::new(system_program.clone(), cpi_accounts), top_up)?;
+            }
+            let cpi_accounts = Allocate { account_to_allocate: info.clone() };
+            system_program::allocate(CpiContext::new_with_signer(system_program.clone(), cpi_accounts, signer_seeds), DENY_LIST_ENTRY_SPACE as u64)?;
+            let cpi_accounts = Assign { account_to_assign: info.clone() };
+            system_program::assign(CpiContext::new_with_signer(system_program.clone(), cpi_accounts, signer_seeds), &crate::ID)?;
+        }
+
+        let entry = DenyListEntry {
+            address: *address,
+            reason: 0,
+            added_by: payer.key(),
+            added_at: now,
+            expires_at: 0,
+            bump,
+        };
+        entry.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+    }
+    Ok(())
+}
@@ -0,0 +1,20 @@
+use anchor_lang::prelude::*;
+
+use crate::{ErrorCode, NullifierRecord};
+
+impl NullifierRecord {
+    /// A freshly created record is zeroed; `spend` stamps it with the (non-zero) cluster time
+    pub fn is_spent(&self) -> bool {
+        self.spent_at != 0
+    }
+
+    /// Mark `nullifier` as spent at unix time `now`. The record is `init_if_needed`, so a
+    /// second spend loads the existing record and is rejected here.
+    pub fn spend(&mut self, nullifier: [u8; 32], now: i64, bump: u8) -> Result<()> {
+        require!(!self.is_spent(), ErrorCode::NullifierAlreadyUsed);
+        self.nullifier = nullifier;
+        self.spent_at = now;
+        self.bump = bump;
+        Ok(())
+    }
+}
@@ -0,0 +1,23 @@
+use anchor_lang::prelude::*;
+
+use crate::{ErrorCode, PoolConfig};
+
+pub const PAUSE_DEPOSITS: u8 = 1 << 0;
+/// Direct `withdraw`
+pub const PAUSE_WITHDRAWALS: u8 = 1 << 1;
+pub const PAUSE_RELAYER_WITHDRAWALS: u8 = 1 << 2;
+
+pub const PAUSE_ALL: u8 = PAUSE_DEPOSITS | PAUSE_WITHDRAWALS | PAUSE_RELAYER_WITHDRAWALS;
+
+impl PoolConfig {
+    /// Whether `flag` is paused at unix time `now`. A pause with `paused_until` set lifts
+    /// itself at that time without another transaction.
+    pub fn is_paused(&self, flag: u8, now: i64) -> bool {
+        self.pause_flags & flag != 0 && (self.paused_until == 0 || now < self.paused_until)
+    }
+
+    pub fn require_not_paused(&self, flag: u8, now: i64) -> Result<()> {
+        require!(!self.is_paused(flag, now), ErrorCode::ContractPaused);
+        Ok(())
+    }
+}
@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+use solana_sha256_hasher::hashv;
+
+/// Number of public inputs exposed by the withdrawal circuit
+pub const WITHDRAW_PUBLIC_INPUTS: usize = 6;
+
+/// Public inputs of the withdrawal circuit, in circuit order.
+///
+/// Binding recipient, amount, relayer and fee into the proof stops anyone who sees a
+/// pending withdrawal from replaying the proof with a different destination or payout.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WithdrawPublicInputs {
+    pub root: [u8; 32],
+    pub nullifier: [u8; 32],
+    /// Token account credited with `amount - fee`
+    pub recipient: Pubkey,
+    pub amount: u64,
+    /// Relayer receiving `fee`; `Pubkey::default()` for direct withdrawals
+    pub relayer: Pubkey,
+    pub fee: u64,
+}
+
+impl WithdrawPublicInputs {
+    /// Encode as 32-byte big-endian BN254 scalars: root and nullifier are passed through
+    /// (both are already field elements), pubkeys go through `hash_to_field` and
+    /// amounts are zero-extended integers.
+    ///
+    /// Clients must produce exactly these values as the circuit's public inputs.
+    pub fn to_field_elements(&self) -> [[u8; 32]; WITHDRAW_PUBLIC_INPUTS] {
+        [
+            self.root,
+            self.nullifier,
+            pubkey_to_field(&self.recipient),
+            u64_to_field(self.amount),
+            pubkey_to_field(&self.relayer),
+            u64_to_field(self.fee),
+        ]
+    }
+}
+
+/// SHA-256 of the concatenated inputs with the most significant byte cleared, so the
+/// result is always a canonical BN254 scalar (< 2^248 < r).
+pub fn hash_to_field(vals: &[&[u8]]) -> [u8; 32] {
+    let mut out = hashv(vals).to_bytes();
+    out[0] = 0;
+    out
+}
+
+/// A 32-byte pubkey may exceed the field modulus, so it is hashed into the field
+pub fn pubkey_to_field(key: &Pubkey) -> [u8; 32] {
+    hash_to_field(&[key.as_ref()])
+}
+
+/// Big-endian, left-padded to 32 bytes
+pub fn u64_to_field(value: u64) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    out[24..].copy_from_slice(&value.to_be_bytes());
+    out
+}
@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+
+use crate::{ErrorCode, RelayerRecord};
+
+impl RelayerRecord {
+    /// Count a withdrawal of `amount` at unix time `now` against the relayer's limits.
+    ///
+    /// Fixed windows: once `window_seconds` have passed since `window_start` a new window
+    /// opens at `now` and both counters reset. Nothing is updated when a limit is hit.
+    pub fn record_withdrawal(&mut self, now: i64, amount: u64) -> Result<()> {
+        let now = u64::try_from(now).map_err(|_| ErrorCode::RelayerRateLimited)?;
+        if now.saturating_sub(self.window_start) >= self.window_seconds {
+            self.window_start = now;
+            self.count = 0;
+            self.amount = 0;
+        }
+
+        require!(self.count < self.limit, ErrorCode::RelayerRateLimited);
+        let total = self.amount.checked_add(amount).ok_or(ErrorCode::RelayerRateLimited)?;
+        require!(total <= self.amount_limit, ErrorCode::RelayerRateLimited);
+
+        self.count += 1;
+        self.amount = total;
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+use crate::{ErrorCode, PoolConfig, RelayerRecord, RELAYER_STATE_SEED};
+
+impl RelayerRecord {
+    /// Mark the relayer active and count it in `config.relayer_count`; no-op if already active
+    pub fn activate(&mut self, config: &mut PoolConfig) {
+        if !self.active {
+            self.active = true;
+            config.relayer_count += 1;
+        }
+    }
+
+    /// Mark the relayer inactive; no-op if already inactive
+    pub fn deactivate(&mut self, config: &mut PoolConfig) {
+        if self.active {
+            self.active = false;
+            config.relayer_count = config.relayer_count.saturating_sub(1);
+        }
+    }
+
+    /// Update lifetime stats after a relayed withdrawal
+    pub fn record_payout(&mut self, amount: u64, fee: u64) {
+        self.total_withdrawals = self.total_withdrawals.saturating_add(1);
+        self.total_volume = self.total_volume.saturating_add(amount);
+        self.total_fees = self.total_fees.saturating_add(fee);
+    }
+}
+
+/// Relayers whose registry record for the pool of `mint` in `accounts` is active.
+///
+/// Threshold withdrawals pass the co-signers' records as remaining accounts. Each must be
+/// a `RelayerRecord` of that pool owned by this program at its canonical PDA; inactive
+/// records are skipped so their signatures do not count.
+pub fn active_relayers(accounts: &[AccountInfo], mint: &Pubkey) -> Result<Vec<Pubkey>> {
+    let mut out = Vec::with_capacity(accounts.len());
+    for info in accounts {
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::Unauthorized);
+        let record = RelayerRecord::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        require_keys_eq!(record.mint, *mint, ErrorCode::Unauthorized);
+        let expected = Pubkey::create_program_address(&[RELAYER_STATE_SEED, record.mint.as_ref(), record.relayer.as_ref(), &[record.bump]], &crate::ID)
+            .map_err(|_| ErrorCode::Unauthorized)?;
+        require_keys_eq!(info.key(), expected, ErrorCode::Unauthorized);
+        if record.active && !out.contains(&record.relayer) {
+            out.push(record.relayer);
+        }
+    }
+    Ok(out)
+}
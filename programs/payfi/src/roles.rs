@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+use crate::{Admin, ErrorCode, RoleAssignment};
+
+/// May call `set_pause`
+pub const ROLE_PAUSER: u8 = 1 << 0;
+/// Manages the deny list and sanctions root
+pub const ROLE_COMPLIANCE: u8 = 1 << 1;
+/// Queues relayer set changes; configures and slashes relayers
+pub const ROLE_RELAYER_MANAGER: u8 = 1 << 2;
+/// Queues verifier mode and verifier program changes
+pub const ROLE_VERIFIER_CONFIG: u8 = 1 << 3;
+
+pub const ALL_ROLES: u8 = ROLE_PAUSER | ROLE_COMPLIANCE | ROLE_RELAYER_MANAGER | ROLE_VERIFIER_CONFIG;
+
+impl RoleAssignment {
+    pub fn has_role(&self, role: u8) -> bool {
+        self.roles & role == role
+    }
+}
+
+impl Admin {
+    /// Require `signer` to hold `role`. The authority implicitly holds every role; anyone
+    /// else needs it set in their `RoleAssignment` (seeds-checked by the caller).
+    pub fn require_role(&self, signer: &Pubkey, assignment: Option<&RoleAssignment>, role: u8) -> Result<()> {
+        if *signer == self.authority {
+            return Ok(());
+        }
+        let granted = assignment.is_some_and(|a| a.holder == *signer && a.has_role(role));
+        require!(granted, ErrorCode::Unauthorized);
+        Ok(())
+    }
+}
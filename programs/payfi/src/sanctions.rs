@@ -0,0 +1,76 @@
+//! Sanctions list committed to by a single Merkle root.
+//!
+//! The off-chain list is sorted by address bytes, framed by the sentinel leaves
+//! `[0x00; 32]` and `[0xff; 32]`, and padded to a power of two with more `[0xff; 32]`
+//! leaves, so every address that is not on the list falls strictly between two adjacent
+//! leaves. Proving those two leaves are adjacent
+//! members of the tree proves the address is not on the list.
+
+use anchor_lang::prelude::*;
+use solana_sha256_hasher::hashv;
+
+use crate::ErrorCode;
+
+/// Deny-list checked through per-address `DenyListEntry` PDAs
+pub const DENY_LIST_MODE_PDA: u8 = 0;
+/// Deny-list checked against `PoolConfig.sanctions_root` with a non-membership proof
+pub const DENY_LIST_MODE_SANCTIONS_ROOT: u8 = 1;
+
+/// Deepest sanctions tree accepted (2^32 leaves)
+pub const MAX_SANCTIONS_PROOF_DEPTH: usize = 32;
+
+const LEAF_PREFIX: &[u8] = &[0];
+const NODE_PREFIX: &[u8] = &[1];
+
+/// Two adjacent leaves of the sorted sanctions tree that bracket an address
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SanctionsProof {
+    pub low: Pubkey,
+    /// Index of `low`; `high` is at `low_index + 1`
+    pub low_index: u32,
+    pub low_path: Vec<[u8; 32]>,
+    pub high: Pubkey,
+    pub high_path: Vec<[u8; 32]>,
+}
+
+/// Leaf hash; the prefix keeps leaves and inner nodes from being confused
+pub fn sanctions_leaf(address: &Pubkey) -> [u8; 32] {
+    hashv(&[LEAF_PREFIX, address.as_ref()]).to_bytes()
+}
+
+pub fn sanctions_node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    hashv(&[NODE_PREFIX, left, right]).to_bytes()
+}
+
+/// Root reached from `leaf` at `index` along `path` (siblings from the bottom up)
+pub fn sanctions_root_from_path(leaf: [u8; 32], index: u32, path: &[[u8; 32]]) -> [u8; 32] {
+    let mut node = leaf;
+    for (level, sibling) in path.iter().enumerate() {
+        node = if (index >> level) & 1 == 0 {
+            sanctions_node(&node, sibling)
+        } else {
+            sanctions_node(sibling, &node)
+        };
+    }
+    node
+}
+
+/// Require `proof` to show that `address` is not a leaf of the tree with `root`
+pub fn verify_non_membership(root: &[u8; 32], address: &Pubkey, proof: &SanctionsProof) -> Result<()> {
+    require!(proof.low != *address && proof.high != *address, ErrorCode::DenyListBlocked);
+    require!(
+        proof.low.to_bytes() < address.to_bytes() && address.to_bytes() < proof.high.to_bytes(),
+        ErrorCode::InvalidSanctionsProof
+    );
+
+    let depth = proof.low_path.len();
+    require!(depth <= MAX_SANCTIONS_PROOF_DEPTH && proof.high_path.len() == depth, ErrorCode::InvalidSanctionsProof);
+    let high_index = proof.low_index.checked_add(1).ok_or(ErrorCode::InvalidSanctionsProof)?;
+    // both indexes must fit in a tree of this depth, or the path bits would be ignored
+    require!(depth == 32 || (high_index as u64) < (1u64 << depth), ErrorCode::InvalidSanctionsProof);
+
+    let low_root = sanctions_root_from_path(sanctions_leaf(&proof.low), proof.low_index, &proof.low_path);
+    let high_root = sanctions_root_from_path(sanctions_leaf(&proof.high), high_index, &proof.high_path);
+    require!(low_root == *root && high_root == *root, ErrorCode::InvalidSanctionsProof);
+    Ok(())
+}
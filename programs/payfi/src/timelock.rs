@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+
+use crate::roles::{ROLE_RELAYER_MANAGER, ROLE_VERIFIER_CONFIG};
+use crate::{ErrorCode, PendingChange};
+
+/// Longest `verifier_magic` the `PoolConfig` account has room for
+pub const MAX_VERIFIER_MAGIC_LEN: usize = 64;
+/// Upper bound on `Admin.timelock_delay`, so a bad update cannot freeze governance
+pub const MAX_TIMELOCK_DELAY: u64 = 30 * 24 * 60 * 60;
+
+/// Configuration change that only takes effect through the timelock queue
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum ConfigChange {
+    /// 0 = off (direct withdrawals rejected), 1 = stub (the proof must equal `magic`
+    /// exactly), 2 = CPI into `verifier_program`, for the pool of `mint`
+    VerifierMode { mint: Pubkey, mode: u8, magic: Vec<u8> },
+    /// Pin the verifier program the pool of `mint` invokes in mode 2 and the registered
+    /// key it checks proofs against, by address and by `verification_key_hash`
+    VerifierProgram { mint: Pubkey, program: Pubkey, verification_key: Pubkey, verification_key_hash: [u8; 32] },
+    /// Activate `relayer` in the pool of `mint`
+    AddRelayer { mint: Pubkey, relayer: Pubkey },
+    RemoveRelayer { mint: Pubkey, relayer: Pubkey },
+    /// First step of an authority transfer; the new key still has to `accept_authority`
+    ProposeAuthority { new_authority: Pubkey },
+    TimelockDelay { delay: u64 },
+}
+
+impl ConfigChange {
+    /// Borsh size of the largest variant, `VerifierProgram` (`VerifierMode` with the
+    /// longest magic is `1 + 32 + 1 + 4 + MAX_VERIFIER_MAGIC_LEN`)
+    pub const MAX_SPACE: usize = 1 + 32 + 32 + 32 + 32;
+
+    /// Role allowed to queue or cancel this change besides the authority; `None` means
+    /// authority only
+    pub fn role(&self) -> Option<u8> {
+        match self {
+            ConfigChange::VerifierMode { .. } | ConfigChange::VerifierProgram { .. } => Some(ROLE_VERIFIER_CONFIG),
+            ConfigChange::AddRelayer { .. } | ConfigChange::RemoveRelayer { .. } => Some(ROLE_RELAYER_MANAGER),
+            ConfigChange::ProposeAuthority { .. } | ConfigChange::TimelockDelay { .. } => None,
+        }
+    }
+
+    /// Checks that do not depend on state at execution time
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            ConfigChange::VerifierMode { mode, magic, .. } => {
+                require!(*mode <= 2, ErrorCode::InvalidConfigChange);
+                require!(magic.len() <= MAX_VERIFIER_MAGIC_LEN, ErrorCode::InvalidConfigChange);
+            }
+            ConfigChange::VerifierProgram { program, verification_key, .. } => {
+                require!(*program != Pubkey::default(), ErrorCode::InvalidVerifierProgram);
+                require!(*verification_key != Pubkey::default(), ErrorCode::InvalidVerificationKey);
+            }
+            ConfigChange::ProposeAuthority { new_authority } => {
+                require!(*new_authority != Pubkey::default(), ErrorCode::InvalidAuthority);
+            }
+            ConfigChange::TimelockDelay { delay } => {
+                require!(*delay <= MAX_TIMELOCK_DELAY, ErrorCode::InvalidConfigChange);
+            }
+            ConfigChange::AddRelayer { .. } | ConfigChange::RemoveRelayer { .. } => {}
+        }
+        Ok(())
+    }
+}
+
+impl PendingChange {
+    /// Whether the delay has passed at unix time `now`
+    pub fn is_ready(&self, now: i64) -> bool {
+        now >= self.eta
+    }
+}
@@ -0,0 +1,20 @@
+use anchor_lang::prelude::*;
+use solana_sha256_hasher::hashv;
+
+use crate::ErrorCode;
+
+/// SHA-256 of a verifier `VerificationKey` account's data.
+///
+/// The account address does not pin the key on its own: the verifier admin can close the
+/// `[VERIFICATION_KEY_SEED, circuit_id, version]` PDA and register other points there, so
+/// pools pin this hash as well.
+pub fn verification_key_hash(data: &[u8]) -> [u8; 32] {
+    hashv(&[data]).to_bytes()
+}
+
+/// Fail unless `key` is owned by `program` and still holds the key hashed into `expected`
+pub fn check_verification_key(key: &AccountInfo, program: &Pubkey, expected: &[u8; 32]) -> Result<()> {
+    require_keys_eq!(*key.owner, *program, ErrorCode::InvalidVerificationKey);
+    require!(verification_key_hash(&key.try_borrow_data()?) == *expected, ErrorCode::InvalidVerificationKey);
+    Ok(())
+}
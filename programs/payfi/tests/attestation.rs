@@ -0,0 +1,60 @@
+use anchor_lang::prelude::Pubkey;
+use payfi::attestation::{check_attestation_versions, Attestation, ATTESTATION_VERSION};
+use payfi::ed25519::ed25519_instruction_data;
+
+fn sample() -> Attestation {
+    Attestation::new(
+        Pubkey::new_from_array([1u8; 32]),
+        Pubkey::new_from_array([2u8; 32]),
+        [3u8; 32],
+        [4u8; 32],
+        Pubkey::new_from_array([5u8; 32]),
+        100,
+        3,
+        1_700_000_000,
+    )
+}
+
+#[test]
+fn test_message_layout() {
+    let msg = sample().to_message();
+    assert_eq!(msg.len(), 1 + 32 * 6 + 8 + 8 + 8);
+    assert_eq!(msg[0], ATTESTATION_VERSION);
+    assert_eq!(&msg[1..33], payfi::ID.as_ref());
+    assert_eq!(&msg[33..65], &[1u8; 32]); // pool
+    assert_eq!(&msg[193..201], &100u64.to_le_bytes());
+    assert_eq!(&msg[201..209], &3u64.to_le_bytes());
+    assert_eq!(&msg[209..], &1_700_000_000u64.to_le_bytes());
+}
+
+#[test]
+fn test_round_trip() {
+    let a = sample();
+    assert_eq!(Attestation::decode(&a.to_message()).unwrap(), a);
+}
+
+#[test]
+fn test_domain_separation() {
+    let base = sample().to_message();
+    let other_pool = Attestation { pool: Pubkey::new_from_array([9u8; 32]), ..sample() };
+    let other_program = Attestation { program_id: Pubkey::new_from_array([9u8; 32]), ..sample() };
+    let other_mint = Attestation { mint: Pubkey::new_from_array([9u8; 32]), ..sample() };
+    let other_fee = Attestation { fee: 4, ..sample() };
+    for a in [other_pool, other_program, other_mint, other_fee] {
+        assert_ne!(a.to_message(), base);
+    }
+}
+
+#[test]
+fn test_rejects_unknown_version() {
+    let future = Attestation { version: ATTESTATION_VERSION + 1, ..sample() }.to_message();
+    assert!(Attestation::decode(&future).is_err());
+    assert!(Attestation::decode(&[]).is_err());
+
+    let key = Pubkey::new_from_array([7u8; 32]);
+    let sig = [0u8; 64];
+    let current = ed25519_instruction_data(&key, &sig, &sample().to_message());
+    assert!(check_attestation_versions(std::slice::from_ref(&current)).is_ok());
+    let stale = ed25519_instruction_data(&key, &sig, &future);
+    assert!(check_attestation_versions(&[current, stale]).is_err());
+}
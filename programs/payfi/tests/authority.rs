@@ -0,0 +1,36 @@
+use anchor_lang::prelude::Pubkey;
+use payfi::Admin;
+
+mod common;
+
+fn admin(authority: Pubkey) -> Admin {
+    Admin { authority, ..common::admin() }
+}
+
+#[test]
+fn test_propose_then_accept() {
+    let (old, new) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mut a = admin(old);
+    a.propose_authority(new).unwrap();
+    assert_eq!(a.authority, old, "control moves only on accept");
+
+    assert!(a.accept_authority(Pubkey::new_unique()).is_err());
+    assert_eq!(a.accept_authority(new).unwrap(), old);
+    assert_eq!(a.authority, new);
+    assert_eq!(a.pending_authority, None);
+    assert!(a.accept_authority(new).is_err(), "proposal is single-use");
+}
+
+#[test]
+fn test_cancel_and_repropose() {
+    let mut a = admin(Pubkey::new_unique());
+    assert!(a.cancel_authority_transfer().is_err());
+    assert!(a.propose_authority(Pubkey::default()).is_err());
+
+    let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+    a.propose_authority(first).unwrap();
+    a.propose_authority(second).unwrap();
+    assert!(a.accept_authority(first).is_err(), "a new proposal replaces the old one");
+    assert_eq!(a.cancel_authority_transfer().unwrap(), second);
+    assert!(a.accept_authority(second).is_err());
+}
@@ -0,0 +1,88 @@
+use anchor_lang::prelude::Pubkey;
+use payfi::bond::{check_unbonding_period, DEFAULT_UNBONDING_PERIOD, MIN_UNBONDING_PERIOD};
+use payfi::RelayerRecord;
+
+fn bonded(bond: u64) -> RelayerRecord {
+    RelayerRecord {
+        relayer: Pubkey::default(),
+        mint: Pubkey::default(),
+        active: false,
+        metadata_url_hash: [0u8; 32],
+        fee_bps: 0,
+        window_start: 0,
+        count: 0,
+        limit: 1,
+        window_seconds: 60,
+        amount: 0,
+        amount_limit: u64::MAX,
+        bond,
+        unbond_ready_at: 0,
+        total_withdrawals: 0,
+        total_volume: 0,
+        total_fees: 0,
+        bump: 0,
+    }
+}
+
+#[test]
+fn test_bond_required_for_registration() {
+    let mut s = bonded(0);
+    assert!(!s.is_bonded(0), "a zero bond never counts");
+    s.add_bond(500).unwrap();
+    assert!(s.is_bonded(500));
+    assert!(!s.is_bonded(501));
+}
+
+#[test]
+fn test_slash_bounded_by_bond() {
+    let mut s = bonded(100);
+    assert!(s.slash(0).is_err());
+    assert!(s.slash(101).is_err());
+    assert_eq!(s.bond, 100);
+    s.slash(40).unwrap();
+    assert_eq!(s.bond, 60);
+    assert!(!s.is_bonded(100));
+}
+
+#[test]
+fn test_unbonding_period() {
+    let mut s = bonded(100);
+    assert!(s.release_bond(1_000).is_err(), "unbond not requested");
+
+    s.request_unbond(1_000, 60).unwrap();
+    assert!(s.is_unbonding());
+    assert!(!s.is_bonded(0));
+    assert!(s.add_bond(1).is_err());
+    assert!(s.request_unbond(1_001, 60).is_err());
+
+    assert!(s.release_bond(1_059).is_err());
+    // still slashable while unbonding
+    s.slash(30).unwrap();
+    assert_eq!(s.release_bond(1_060).unwrap(), 70);
+    assert_eq!(s.bond, 0);
+    assert!(!s.is_unbonding());
+}
+
+#[test]
+fn test_release_time_fixed_at_request() {
+    let mut s = bonded(100);
+    s.request_unbond(1_000, MIN_UNBONDING_PERIOD).unwrap();
+    assert_eq!(s.unbond_ready_at, 1_000 + MIN_UNBONDING_PERIOD);
+    // the period is no longer an input, so shortening it later cannot release early
+    assert!(s.release_bond(1_000 + MIN_UNBONDING_PERIOD as i64 - 1).is_err());
+    assert_eq!(s.release_bond(1_000 + MIN_UNBONDING_PERIOD as i64).unwrap(), 100);
+}
+
+#[test]
+fn test_unbonding_period_lower_bound() {
+    assert!(check_unbonding_period(0).is_err());
+    assert!(check_unbonding_period(MIN_UNBONDING_PERIOD - 1).is_err());
+    assert!(check_unbonding_period(MIN_UNBONDING_PERIOD).is_ok());
+    assert!(check_unbonding_period(DEFAULT_UNBONDING_PERIOD).is_ok());
+}
+
+#[test]
+fn test_unbond_requires_bond() {
+    let mut s = bonded(0);
+    assert!(s.request_unbond(1_000, 60).is_err());
+}
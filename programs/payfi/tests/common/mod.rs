@@ -0,0 +1,41 @@
+// each suite uses only some of these
+#![allow(dead_code)]
+
+use anchor_lang::prelude::Pubkey;
+use payfi::{Admin, PoolConfig};
+
+/// `Admin` with every field zeroed or permissive; tests override what they exercise
+pub fn admin() -> Admin {
+    Admin {
+        version: 0,
+        authority: Pubkey::default(),
+        pending_authority: None,
+        unbonding_period: 0,
+        timelock_delay: 0,
+        next_change_id: 0,
+        bump: 0,
+    }
+}
+
+/// `PoolConfig` with every field zeroed or permissive; tests override what they exercise
+pub fn pool_config() -> PoolConfig {
+    PoolConfig {
+        version: 0,
+        mint: Pubkey::default(),
+        deny_list_mode: 0,
+        sanctions_root: [0u8; 32],
+        verifier_mode: 0,
+        verifier_magic: vec![],
+        verifier_program: Pubkey::default(),
+        verification_key: Pubkey::default(),
+        verification_key_hash: [0u8; 32],
+        pause_flags: 0,
+        paused_until: 0,
+        attestation_threshold: 1,
+        threshold_amount: u64::MAX,
+        max_fee_bps: 0,
+        relayer_count: 0,
+        min_relayer_bond: 0,
+        bump: 0,
+    }
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::{AccountInfo, Pubkey};
+use anchor_lang::AccountSerialize;
+use payfi::deny_list::{check_not_denied, DENY_LIST_ENTRY_SPACE};
+use payfi::DenyListEntry;
+
+fn entry(expires_at: i64) -> DenyListEntry {
+    DenyListEntry {
+        address: Pubkey::new_unique(),
+        reason: 7,
+        added_by: Pubkey::new_unique(),
+        added_at: 1_000,
+        expires_at,
+        bump: 255,
+    }
+}
+
+fn serialize(entry: &DenyListEntry) -> Vec<u8> {
+    let mut data = Vec::new();
+    entry.try_serialize(&mut data).unwrap();
+    data
+}
+
+#[test]
+fn test_expiry() {
+    assert!(entry(0).is_active(i64::MAX), "0 never expires");
+    assert!(entry(2_000).is_active(1_999));
+    assert!(!entry(2_000).is_active(2_000));
+}
+
+#[test]
+fn test_missing_entry_is_not_denied() {
+    let key = Pubkey::new_unique();
+    let (mut lamports, mut data) = (0u64, Vec::new());
+    let system = Pubkey::default();
+    let info = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &system, false, 0);
+    assert!(check_not_denied(&info, 1_500).is_ok());
+}
+
+#[test]
+fn test_active_entry_blocks_until_expiry() {
+    let key = Pubkey::new_unique();
+    let (mut lamports, mut data) = (1u64, serialize(&entry(2_000)));
+    let info = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &payfi::ID, false, 0);
+    assert!(check_not_denied(&info, 1_500).is_err());
+    assert!(check_not_denied(&info, 2_000).is_ok());
+}
+
+#[test]
+fn test_entry_space() {
+    assert_eq!(serialize(&entry(0)).len(), DENY_LIST_ENTRY_SPACE);
+}
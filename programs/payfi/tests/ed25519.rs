@@ -0,0 +1,186 @@
+use anchor_lang::prelude::Pubkey;
+use payfi::ed25519::{
+    attesting_signers, ed25519_instruction_data, ed25519_signed_messages, verify_ed25519_instruction, Ed25519SignatureOffsets,
+    CURRENT_INSTRUCTION, HEADER_LEN, OFFSETS_LEN,
+};
+
+const SIG: [u8; 64] = [9u8; 64];
+
+fn relayer() -> Pubkey {
+    Pubkey::new_from_array([1u8; 32])
+}
+
+fn message() -> Vec<u8> {
+    (0u8..88).collect()
+}
+
+fn offsets(data: &[u8]) -> Ed25519SignatureOffsets {
+    let b = &data[HEADER_LEN..HEADER_LEN + OFFSETS_LEN];
+    let f = |i: usize| u16::from_le_bytes([b[2 * i], b[2 * i + 1]]);
+    Ed25519SignatureOffsets {
+        signature_offset: f(0),
+        signature_instruction_index: f(1),
+        public_key_offset: f(2),
+        public_key_instruction_index: f(3),
+        message_data_offset: f(4),
+        message_data_size: f(5),
+        message_instruction_index: f(6),
+    }
+}
+
+fn with_offsets(mut data: Vec<u8>, o: Ed25519SignatureOffsets) -> Vec<u8> {
+    data[HEADER_LEN..HEADER_LEN + OFFSETS_LEN].copy_from_slice(&o.to_bytes());
+    data
+}
+
+#[test]
+fn test_accepts_matching_signer_and_message() {
+    let data = ed25519_instruction_data(&relayer(), &SIG, &message());
+    assert!(verify_ed25519_instruction(&data, &relayer(), &message()).is_ok());
+    // web3.js Ed25519Program layout: pubkey at 16, signature at 48, message at 112
+    let o = offsets(&data);
+    assert_eq!((o.public_key_offset, o.signature_offset, o.message_data_offset), (16, 48, 112));
+}
+
+#[test]
+fn test_rejects_other_signer_or_message() {
+    let data = ed25519_instruction_data(&relayer(), &SIG, &message());
+    assert!(verify_ed25519_instruction(&data, &Pubkey::new_from_array([2u8; 32]), &message()).is_err());
+    let mut other = message();
+    other[0] ^= 1;
+    assert!(verify_ed25519_instruction(&data, &relayer(), &other).is_err());
+}
+
+#[test]
+fn test_rejects_offsets_into_other_instructions() {
+    // pointing any field at another instruction (e.g. our own instruction data, which
+    // carries the expected bytes) would let the precompile verify something else
+    let data = ed25519_instruction_data(&relayer(), &SIG, &message());
+    let base = offsets(&data);
+    for spoof in [
+        Ed25519SignatureOffsets { signature_instruction_index: 0, ..base },
+        Ed25519SignatureOffsets { public_key_instruction_index: 1, ..base },
+        Ed25519SignatureOffsets { message_instruction_index: 2, ..base },
+    ] {
+        let spoofed = with_offsets(data.clone(), spoof);
+        assert!(verify_ed25519_instruction(&spoofed, &relayer(), &message()).is_err());
+    }
+}
+
+#[test]
+fn test_rejects_truncated_message() {
+    // a signature over a prefix of the expected payload must not count
+    let data = ed25519_instruction_data(&relayer(), &SIG, &message());
+    let base = offsets(&data);
+    let spoofed = with_offsets(data, Ed25519SignatureOffsets { message_data_size: base.message_data_size - 8, ..base });
+    assert!(verify_ed25519_instruction(&spoofed, &relayer(), &message()).is_err());
+}
+
+#[test]
+fn test_rejects_out_of_bounds_offsets() {
+    let data = ed25519_instruction_data(&relayer(), &SIG, &message());
+    let base = offsets(&data);
+    let len = data.len() as u16;
+    for spoof in [
+        Ed25519SignatureOffsets { signature_offset: len - 10, ..base },
+        Ed25519SignatureOffsets { public_key_offset: len - 10, ..base },
+        Ed25519SignatureOffsets { message_data_size: base.message_data_size + 1, ..base },
+        Ed25519SignatureOffsets { message_data_offset: u16::MAX, ..base },
+    ] {
+        assert!(verify_ed25519_instruction(&with_offsets(data.clone(), spoof), &relayer(), &message()).is_err());
+    }
+    assert!(verify_ed25519_instruction(&data[..data.len() - 1], &relayer(), &message()).is_err());
+}
+
+#[test]
+fn test_rejects_missing_or_extra_signatures() {
+    let data = ed25519_instruction_data(&relayer(), &SIG, &message());
+    let mut none = data.clone();
+    none[0] = 0;
+    assert!(verify_ed25519_instruction(&none, &relayer(), &message()).is_err());
+
+    // claims two signatures but only carries offsets for one
+    let mut truncated = data.clone();
+    truncated[0] = 2;
+    assert!(ed25519_signed_messages(&truncated).is_err());
+    assert!(verify_ed25519_instruction(&[], &relayer(), &message()).is_err());
+
+    // a second, valid signature by someone else is not accepted alongside ours
+    let two = two_signatures(&relayer(), &Pubkey::new_from_array([2u8; 32]), &message());
+    assert_eq!(ed25519_signed_messages(&two).unwrap().len(), 2);
+    assert!(verify_ed25519_instruction(&two, &relayer(), &message()).is_err());
+}
+
+/// Two self-contained signatures over the same message
+fn two_signatures(a: &Pubkey, b: &Pubkey, message: &[u8]) -> Vec<u8> {
+    let header = HEADER_LEN + 2 * OFFSETS_LEN;
+    let message_offset = header + 2 * (32 + 64);
+    let mut data = vec![2u8, 0u8];
+    for i in 0..2 {
+        let key_offset = header + i * (32 + 64);
+        data.extend_from_slice(
+            &Ed25519SignatureOffsets {
+                signature_offset: (key_offset + 32) as u16,
+                signature_instruction_index: CURRENT_INSTRUCTION,
+                public_key_offset: key_offset as u16,
+                public_key_instruction_index: CURRENT_INSTRUCTION,
+                message_data_offset: message_offset as u16,
+                message_data_size: message.len() as u16,
+                message_instruction_index: CURRENT_INSTRUCTION,
+            }
+            .to_bytes(),
+        );
+    }
+    for key in [a, b] {
+        data.extend_from_slice(key.as_ref());
+        data.extend_from_slice(&SIG);
+    }
+    data.extend_from_slice(message);
+    data
+}
+
+fn key(n: u8) -> Pubkey {
+    Pubkey::new_from_array([n; 32])
+}
+
+#[test]
+fn test_threshold_counts_distinct_registered_signers() {
+    let relayers = [key(1), key(2), key(3)];
+    // one instruction per signer (web3.js style) and one instruction with two signatures
+    let separate = vec![
+        ed25519_instruction_data(&key(1), &SIG, &message()),
+        ed25519_instruction_data(&key(3), &SIG, &message()),
+    ];
+    assert_eq!(attesting_signers(&separate, &message(), &relayers).unwrap(), vec![key(1), key(3)]);
+    let combined = vec![two_signatures(&key(2), &key(3), &message())];
+    assert_eq!(attesting_signers(&combined, &message(), &relayers).unwrap().len(), 2);
+}
+
+#[test]
+fn test_threshold_rejects_duplicate_signer() {
+    let relayers = [key(1), key(2)];
+    let dup = vec![
+        ed25519_instruction_data(&key(1), &SIG, &message()),
+        ed25519_instruction_data(&key(1), &SIG, &message()),
+    ];
+    assert!(attesting_signers(&dup, &message(), &relayers).is_err());
+    assert!(attesting_signers(&[two_signatures(&key(2), &key(2), &message())], &message(), &relayers).is_err());
+}
+
+#[test]
+fn test_threshold_rejects_unregistered_signer_or_other_message() {
+    let relayers = [key(1), key(2)];
+    let outsider = vec![
+        ed25519_instruction_data(&key(1), &SIG, &message()),
+        ed25519_instruction_data(&key(9), &SIG, &message()),
+    ];
+    assert!(attesting_signers(&outsider, &message(), &relayers).is_err());
+
+    let mut other = message();
+    other[5] ^= 1;
+    let mixed = vec![
+        ed25519_instruction_data(&key(1), &SIG, &message()),
+        ed25519_instruction_data(&key(2), &SIG, &other),
+    ];
+    assert!(attesting_signers(&mixed, &message(), &relayers).is_err());
+}
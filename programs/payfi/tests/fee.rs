@@ -0,0 +1,23 @@
+use payfi::fee::check_relayer_fee;
+
+#[test]
+fn test_fee_within_cap() {
+    // 1% of 10_000
+    assert!(check_relayer_fee(10_000, 0, 100).is_ok());
+    assert!(check_relayer_fee(10_000, 100, 100).is_ok());
+    assert!(check_relayer_fee(10_000, 101, 100).is_err());
+}
+
+#[test]
+fn test_zero_cap_forbids_fees() {
+    assert!(check_relayer_fee(10_000, 0, 0).is_ok());
+    assert!(check_relayer_fee(10_000, 1, 0).is_err());
+}
+
+#[test]
+fn test_cap_rounds_down_and_never_exceeds_amount() {
+    // 1% of 99 is 0.99, rounded down to 0
+    assert!(check_relayer_fee(99, 1, 100).is_err());
+    assert!(check_relayer_fee(u64::MAX, u64::MAX, 10_000).is_ok());
+    assert!(check_relayer_fee(100, 101, u16::MAX).is_err());
+}
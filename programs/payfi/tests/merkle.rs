@@ -0,0 +1,162 @@
+use payfi::merkle::{compute_root, empty_root, hash_pair, zero_hashes, MERKLE_TREE_DEPTH};
+use payfi::TreeState;
+
+fn tree_with_history(root_history_size: usize) -> TreeState {
+    let mut tree = TreeState {
+        version: 0,
+        root: [0u8; 32],
+        next_index: 0,
+        filled_subtrees: [[0u8; 32]; MERKLE_TREE_DEPTH],
+        zeros: [[0u8; 32]; MERKLE_TREE_DEPTH],
+        roots: vec![],
+        current_root_index: 0,
+        bump: 0,
+    };
+    tree.init_tree(root_history_size);
+    tree
+}
+
+fn empty_tree() -> TreeState {
+    tree_with_history(30)
+}
+
+fn leaf(n: u8) -> [u8; 32] {
+    let mut l = [0u8; 32];
+    l[31] = n;
+    l
+}
+
+/// Vector shared with the Noir circuit's tests (`zk/noir/src/main.nr`)
+const SHARED_VECTOR: &str = include_str!("../../../zk/noir/vectors/merkle_path.json");
+
+fn unhex(s: &str) -> [u8; 32] {
+    let bytes: Vec<u8> = (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect();
+    bytes.try_into().unwrap()
+}
+
+/// Reference implementation: build every level from the full leaf list and return the path for `index`
+fn naive_path(leaves: &[[u8; 32]], index: usize) -> [[u8; 32]; MERKLE_TREE_DEPTH] {
+    let zeros = zero_hashes();
+    let mut level: Vec<[u8; 32]> = leaves.to_vec();
+    let mut path = [[0u8; 32]; MERKLE_TREE_DEPTH];
+    let mut idx = index;
+    for (height, node) in path.iter_mut().enumerate() {
+        let sibling = idx ^ 1;
+        *node = level.get(sibling).copied().unwrap_or(zeros[height]);
+        let mut next = Vec::new();
+        for pair in level.chunks(2) {
+            let right = pair.get(1).copied().unwrap_or(zeros[height]);
+            next.push(hash_pair(&pair[0], &right));
+        }
+        level = next;
+        idx /= 2;
+    }
+    path
+}
+
+#[test]
+fn test_empty_tree_root() {
+    let tree = empty_tree();
+    assert_eq!(tree.next_index, 0);
+    assert_eq!(tree.root, empty_root());
+    assert_ne!(tree.root, [0u8; 32]);
+}
+
+#[test]
+fn test_nodes_are_field_elements() {
+    let h = hash_pair(&[0xffu8; 32], &[0xffu8; 32]);
+    assert_eq!(h[0], 0, "top byte must be cleared so nodes stay below the BN254 modulus");
+}
+
+#[test]
+fn test_append_matches_reference_tree() {
+    let mut tree = empty_tree();
+    let leaves: Vec<[u8; 32]> = (1..=7).map(leaf).collect();
+
+    for (i, l) in leaves.iter().enumerate() {
+        let index = tree.append_leaf(*l).unwrap();
+        assert_eq!(index, i as u64);
+
+        // every leaf inserted so far must prove membership against the new root
+        for (j, earlier) in leaves[..=i].iter().enumerate() {
+            let path = naive_path(&leaves[..=i], j);
+            assert_eq!(compute_root(*earlier, j as u64, &path), tree.root);
+        }
+    }
+    assert_eq!(tree.next_index, 7);
+}
+
+#[test]
+fn test_shared_vector_matches_circuit() {
+    let v: serde_json::Value = serde_json::from_str(SHARED_VECTOR).unwrap();
+    let list = |k: &str| -> Vec<[u8; 32]> {
+        v[k].as_array().unwrap().iter().map(|x| unhex(x.as_str().unwrap())).collect()
+    };
+    let leaves = list("leaves");
+    let index = v["index"].as_u64().unwrap();
+    let path: [[u8; 32]; MERKLE_TREE_DEPTH] = list("path").try_into().unwrap();
+    let root = unhex(v["root"].as_str().unwrap());
+
+    let mut tree = empty_tree();
+    for l in &leaves {
+        tree.append_leaf(*l).unwrap();
+    }
+    assert_eq!(tree.root, root);
+    assert_eq!(path, naive_path(&leaves, index as usize));
+    assert_eq!(compute_root(leaves[index as usize], index, &path), root);
+
+    let secret = unhex(v["secret"].as_str().unwrap());
+    let nullifier = unhex(v["nullifier"].as_str().unwrap());
+    assert_eq!(hash_pair(&leaves[index as usize], &secret), nullifier);
+}
+
+#[test]
+fn test_root_changes_on_every_deposit() {
+    let mut tree = empty_tree();
+    let r0 = tree.root;
+    tree.append_leaf(leaf(1)).unwrap();
+    let r1 = tree.root;
+    tree.append_leaf(leaf(1)).unwrap();
+    let r2 = tree.root;
+    assert_ne!(r0, r1);
+    assert_ne!(r1, r2, "identical commitments still occupy distinct leaves");
+}
+
+#[test]
+fn test_full_tree_rejects_append() {
+    let mut tree = empty_tree();
+    tree.next_index = 1u64 << MERKLE_TREE_DEPTH;
+    assert!(tree.append_leaf(leaf(1)).is_err());
+}
+
+#[test]
+fn test_root_history_accepts_recent_roots() {
+    let mut tree = tree_with_history(3);
+    let r0 = tree.root;
+    tree.append_leaf(leaf(1)).unwrap();
+    let r1 = tree.root;
+    tree.append_leaf(leaf(2)).unwrap();
+    let r2 = tree.root;
+
+    // proof generated against r1 stays valid after a concurrent deposit
+    assert!(tree.is_known_root(&r0));
+    assert!(tree.is_known_root(&r1));
+    assert!(tree.is_known_root(&r2));
+
+    // fourth root evicts the oldest entry
+    tree.append_leaf(leaf(3)).unwrap();
+    let r3 = tree.root;
+    assert!(!tree.is_known_root(&r0));
+    assert!(tree.is_known_root(&r1));
+    assert!(tree.is_known_root(&r3));
+}
+
+#[test]
+fn test_root_history_rejects_unknown_and_zero_roots() {
+    let tree = tree_with_history(5);
+    assert!(!tree.is_known_root(&[0u8; 32]), "zeroed ring slots must not be accepted");
+    assert!(!tree.is_known_root(&leaf(42)));
+}
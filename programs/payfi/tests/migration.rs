@@ -0,0 +1,92 @@
+use anchor_lang::prelude::{AccountInfo, Pubkey};
+use anchor_lang::{AccountSerialize, AnchorSerialize, Discriminator};
+use payfi::migration::{restore_deny_list, upgrade_admin, LegacyAdminV0, ADMIN_SPACE, ADMIN_VERSION, LEGACY_ADMIN_V0_SPACE};
+use payfi::Admin;
+
+fn legacy(paused: bool, verifier_mode: u8) -> LegacyAdminV0 {
+    LegacyAdminV0 {
+        authority: Pubkey::new_unique(),
+        deny_list: vec![Pubkey::new_unique()],
+        relayers: vec![Pubkey::new_unique(), Pubkey::new_unique()],
+        verifier_mode,
+        verifier_magic: b"magic".to_vec(),
+        paused,
+        bump: 254,
+    }
+}
+
+/// Account data as the unversioned program left it: discriminator, Borsh body, zero padding
+fn legacy_account(admin: &LegacyAdminV0) -> Vec<u8> {
+    let mut data = Admin::DISCRIMINATOR.to_vec();
+    admin.serialize(&mut data).unwrap();
+    data.resize(LEGACY_ADMIN_V0_SPACE, 0);
+    data
+}
+
+#[test]
+fn test_admin_space_fits_largest_admin() {
+    let mut admin = legacy(false, 1).upgrade();
+    admin.pending_authority = Some(Pubkey::new_unique());
+    let mut data = Vec::new();
+    admin.try_serialize(&mut data).unwrap();
+    assert_eq!(data.len(), ADMIN_SPACE);
+    assert_ne!(ADMIN_SPACE, LEGACY_ADMIN_V0_SPACE, "sizes tell the layouts apart");
+}
+
+#[test]
+fn test_upgrades_legacy_layout() {
+    let old = legacy(true, 1);
+    let migration = upgrade_admin(&legacy_account(&old)).unwrap();
+    assert_eq!(migration.from_version, 0);
+    assert_eq!(migration.legacy_relayers, old.relayers);
+    assert_eq!(migration.legacy_deny_list, old.deny_list);
+    assert!(migration.legacy_paused);
+    assert_eq!(migration.legacy_verifier_mode, 1);
+    assert_eq!(migration.legacy_verifier_magic, old.verifier_magic);
+
+    let admin = migration.admin;
+    assert_eq!(admin.version, ADMIN_VERSION);
+    assert_eq!(admin.authority, old.authority);
+    assert_eq!(admin.bump, 254);
+}
+
+#[test]
+fn test_legacy_cpi_mode_is_handed_back() {
+    // mode 2 is not applied to any pool until a VerifierProgram change pins a program and key
+    let migration = upgrade_admin(&legacy_account(&legacy(false, 2))).unwrap();
+    assert_eq!(migration.legacy_verifier_mode, 2);
+    assert!(!migration.legacy_paused);
+}
+
+#[test]
+fn test_current_layout_is_not_migrated_again() {
+    let mut data = Vec::new();
+    legacy(false, 0).upgrade().try_serialize(&mut data).unwrap();
+    data.resize(ADMIN_SPACE, 0);
+    assert!(upgrade_admin(&data).is_err());
+}
+
+#[test]
+fn test_rejects_unknown_data() {
+    let mut data = legacy_account(&legacy(false, 0));
+    data[0] ^= 1;
+    assert!(upgrade_admin(&data).is_err(), "wrong discriminator");
+    assert!(upgrade_admin(&[]).is_err());
+
+    let mut data = Admin::DISCRIMINATOR.to_vec();
+    data.resize(ADMIN_SPACE, 0);
+    data[8] = ADMIN_VERSION + 1;
+    assert!(upgrade_admin(&data).is_err(), "future version");
+}
+
+#[test]
+fn test_deny_list_restore_needs_every_entry() {
+    let old = legacy(false, 0);
+    let (payer, system) = (Pubkey::new_unique(), Pubkey::default());
+    let (mut payer_lamports, mut payer_data) = (1_000_000u64, Vec::new());
+    let (mut system_lamports, mut system_data) = (1u64, Vec::new());
+    let payer = AccountInfo::new(&payer, true, true, &mut payer_lamports, &mut payer_data, &system, false, 0);
+    let system_program = AccountInfo::new(&system, false, false, &mut system_lamports, &mut system_data, &system, true, 0);
+    // dropping the legacy entries' accounts would silently unblock those addresses
+    assert!(restore_deny_list(&old.deny_list, &[], &payer, &system_program, 1_000).is_err());
+}
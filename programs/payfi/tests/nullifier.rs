@@ -0,0 +1,54 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::AnchorSerialize;
+use payfi::{ErrorCode, NullifierRecord, NULLIFIER_SEED};
+
+fn nullifier_pda(mint: &Pubkey, nullifier: &[u8; 32]) -> Pubkey {
+    Pubkey::find_program_address(&[NULLIFIER_SEED, mint.as_ref(), nullifier.as_ref()], &payfi::ID).0
+}
+
+#[test]
+fn test_shared_prefix_nullifiers_get_distinct_records() {
+    // identical first 8 bytes used to map to the same chunk bit
+    let mut a = [0u8; 32];
+    a[..8].copy_from_slice(&[7u8; 8]);
+    let mut b = a;
+    b[31] = 1;
+    let mint = Pubkey::new_unique();
+    assert_ne!(nullifier_pda(&mint, &a), nullifier_pda(&mint, &b));
+    assert_eq!(nullifier_pda(&mint, &a), nullifier_pda(&mint, &a));
+}
+
+#[test]
+fn test_pools_keep_separate_nullifier_sets() {
+    let nullifier = [9u8; 32];
+    assert_ne!(nullifier_pda(&Pubkey::new_unique(), &nullifier), nullifier_pda(&Pubkey::new_unique(), &nullifier));
+}
+
+#[test]
+fn test_nullifier_record_space() {
+    let record = NullifierRecord {
+        nullifier: [1u8; 32],
+        spent_at: 1_700_000_000,
+        bump: 255,
+    };
+    assert_eq!(8 + 32 + 8 + 1, 8 + record.try_to_vec().unwrap().len());
+}
+
+#[test]
+fn test_spend_marks_record() {
+    let mut record = NullifierRecord { nullifier: [0u8; 32], spent_at: 0, bump: 0 };
+    assert!(!record.is_spent());
+    record.spend([3u8; 32], 1_700_000_000, 254).unwrap();
+    assert!(record.is_spent());
+    assert_eq!(record.nullifier, [3u8; 32]);
+    assert_eq!(record.bump, 254);
+}
+
+#[test]
+fn test_second_spend_rejected() {
+    let mut record = NullifierRecord { nullifier: [0u8; 32], spent_at: 0, bump: 0 };
+    record.spend([3u8; 32], 1_700_000_000, 254).unwrap();
+    let err = record.spend([3u8; 32], 1_700_000_100, 254).unwrap_err();
+    assert_eq!(err, ErrorCode::NullifierAlreadyUsed.into());
+    assert_eq!(record.spent_at, 1_700_000_000);
+}
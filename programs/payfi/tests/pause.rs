@@ -0,0 +1,24 @@
+use payfi::pause::{PAUSE_DEPOSITS, PAUSE_RELAYER_WITHDRAWALS, PAUSE_WITHDRAWALS};
+use payfi::PoolConfig;
+
+mod common;
+
+fn config(pause_flags: u8, paused_until: i64) -> PoolConfig {
+    PoolConfig { pause_flags, paused_until, ..common::pool_config() }
+}
+
+#[test]
+fn test_flags_are_independent() {
+    let a = config(PAUSE_DEPOSITS | PAUSE_RELAYER_WITHDRAWALS, 0);
+    assert!(a.require_not_paused(PAUSE_DEPOSITS, 1_000).is_err());
+    assert!(a.require_not_paused(PAUSE_RELAYER_WITHDRAWALS, 1_000).is_err());
+    assert!(a.require_not_paused(PAUSE_WITHDRAWALS, 1_000).is_ok());
+}
+
+#[test]
+fn test_auto_unpause() {
+    let a = config(PAUSE_WITHDRAWALS, 2_000);
+    assert!(a.is_paused(PAUSE_WITHDRAWALS, 1_999));
+    assert!(!a.is_paused(PAUSE_WITHDRAWALS, 2_000));
+    assert!(config(PAUSE_WITHDRAWALS, 0).is_paused(PAUSE_WITHDRAWALS, i64::MAX), "0 means no auto-unpause");
+}
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AccountSerialize, AnchorSerialize};
+use payfi::migration::{POOL_CONFIG_SPACE, VAULT_VERSION};
+use payfi::timelock::MAX_VERIFIER_MAGIC_LEN;
+use payfi::{PoolConfig, Vault, NULLIFIER_MANAGER_SEED, POOL_CONFIG_SEED, TREE_STATE_SEED, VAULT_SEED};
+
+mod common;
+
+fn pool_pdas(mint: &Pubkey) -> [Pubkey; 4] {
+    [VAULT_SEED, TREE_STATE_SEED, NULLIFIER_MANAGER_SEED, POOL_CONFIG_SEED].map(|seed| Pubkey::find_program_address(&[seed, mint.as_ref()], &payfi::ID).0)
+}
+
+#[test]
+fn test_each_mint_gets_its_own_pool() {
+    let usdc = Pubkey::new_unique();
+    let usdt = Pubkey::new_unique();
+    let a = pool_pdas(&usdc);
+    let b = pool_pdas(&usdt);
+    for (x, y) in a.iter().zip(b.iter()) {
+        assert_ne!(x, y);
+    }
+    assert_eq!(a, pool_pdas(&usdc));
+}
+
+#[test]
+fn test_vault_space() {
+    let vault = Vault {
+        version: VAULT_VERSION,
+        mint: Pubkey::new_unique(),
+        token_account: Pubkey::new_unique(),
+        bump: 255,
+    };
+    assert_eq!(8 + 1 + 32 + 32 + 1, 8 + vault.try_to_vec().unwrap().len());
+}
+
+#[test]
+fn test_pool_config_space_fits_largest_config() {
+    let config = PoolConfig { verifier_magic: vec![7u8; MAX_VERIFIER_MAGIC_LEN], ..common::pool_config() };
+    let mut data = Vec::new();
+    config.try_serialize(&mut data).unwrap();
+    assert_eq!(data.len(), POOL_CONFIG_SPACE);
+}
@@ -0,0 +1,78 @@
+use anchor_lang::prelude::Pubkey;
+use payfi::merkle::hash_pair;
+use payfi::public_inputs::{hash_to_field, pubkey_to_field, u64_to_field, WithdrawPublicInputs};
+
+fn unhex(s: &str) -> [u8; 32] {
+    let bytes: Vec<u8> = (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect();
+    bytes.try_into().unwrap()
+}
+
+fn sample() -> WithdrawPublicInputs {
+    let mut root = [0u8; 32];
+    root[31] = 1;
+    let mut nullifier = [0u8; 32];
+    nullifier[31] = 2;
+    WithdrawPublicInputs {
+        root,
+        nullifier,
+        recipient: Pubkey::new_from_array([1u8; 32]),
+        amount: 100,
+        relayer: Pubkey::default(),
+        fee: 0,
+    }
+}
+
+#[test]
+fn test_field_elements_match_verifier_vectors() {
+    // Same values as programs/verifier/tests/fixtures/groth16_withdraw.json
+    let fields = sample().to_field_elements();
+    assert_eq!(fields[2], unhex("00cd6e8422c407fb6d098690f1130b7ded7ec2f7f5e1d30bd9d521f015363793"));
+    assert_eq!(fields[3], unhex("0000000000000000000000000000000000000000000000000000000000000064"));
+    assert_eq!(fields[4], unhex("00687aadf862bd776c8fc18b8e9f8e20089714856ee233b3902a591d0d5f2925"));
+    assert_eq!(fields[5], [0u8; 32]);
+}
+
+#[test]
+fn test_root_and_nullifier_pass_through() {
+    let inputs = sample();
+    let fields = inputs.to_field_elements();
+    assert_eq!(fields[0], inputs.root);
+    assert_eq!(fields[1], inputs.nullifier);
+}
+
+#[test]
+fn test_every_binding_changes_the_encoding() {
+    let base = sample().to_field_elements();
+    let variants = [
+        WithdrawPublicInputs { recipient: Pubkey::new_from_array([2u8; 32]), ..sample() },
+        WithdrawPublicInputs { amount: 101, ..sample() },
+        WithdrawPublicInputs { relayer: Pubkey::new_from_array([3u8; 32]), ..sample() },
+        WithdrawPublicInputs { fee: 1, ..sample() },
+    ];
+    for (i, variant) in variants.iter().enumerate() {
+        let fields = variant.to_field_elements();
+        assert_ne!(fields[2 + i], base[2 + i]);
+        // only the changed input moves
+        for j in (0..base.len()).filter(|j| *j != 2 + i) {
+            assert_eq!(fields[j], base[j]);
+        }
+    }
+}
+
+#[test]
+fn test_encodings_are_canonical_field_elements() {
+    assert_eq!(pubkey_to_field(&Pubkey::new_from_array([0xffu8; 32]))[0], 0);
+    assert_eq!(hash_to_field(&[b"anything"])[0], 0);
+    let max = u64_to_field(u64::MAX);
+    assert_eq!(&max[..24], &[0u8; 24]);
+    assert_eq!(&max[24..], &u64::MAX.to_be_bytes());
+}
+
+#[test]
+fn test_merkle_nodes_use_the_same_reduction() {
+    let (a, b) = ([1u8; 32], [2u8; 32]);
+    assert_eq!(hash_pair(&a, &b), hash_to_field(&[&a, &b]));
+}
@@ -0,0 +1,70 @@
+use anchor_lang::prelude::Pubkey;
+use payfi::RelayerRecord;
+
+fn state(limit: u64, window_seconds: u64, amount_limit: u64) -> RelayerRecord {
+    RelayerRecord {
+        relayer: Pubkey::default(),
+        mint: Pubkey::default(),
+        active: false,
+        metadata_url_hash: [0u8; 32],
+        fee_bps: 0,
+        window_start: 0,
+        count: 0,
+        limit,
+        window_seconds,
+        amount: 0,
+        amount_limit,
+        bond: 0,
+        unbond_ready_at: 0,
+        total_withdrawals: 0,
+        total_volume: 0,
+        total_fees: 0,
+        bump: 0,
+    }
+}
+
+#[test]
+fn test_count_limit_within_window() {
+    let mut s = state(2, 60, u64::MAX);
+    assert!(s.record_withdrawal(1_000, 10).is_ok());
+    assert!(s.record_withdrawal(1_010, 10).is_ok());
+    assert!(s.record_withdrawal(1_020, 10).is_err());
+    assert_eq!(s.count, 2);
+    assert_eq!(s.amount, 20);
+}
+
+#[test]
+fn test_window_rolls_over() {
+    let mut s = state(1, 60, u64::MAX);
+    assert!(s.record_withdrawal(1_000, 10).is_ok());
+    assert!(s.record_withdrawal(1_059, 10).is_err());
+    assert!(s.record_withdrawal(1_060, 10).is_ok());
+    assert_eq!(s.window_start, 1_060);
+    assert_eq!(s.count, 1);
+    assert_eq!(s.amount, 10);
+}
+
+#[test]
+fn test_amount_limit_within_window() {
+    let mut s = state(10, 60, 100);
+    assert!(s.record_withdrawal(1_000, 60).is_ok());
+    assert!(s.record_withdrawal(1_001, 41).is_err());
+    // a rejected withdrawal leaves the counters untouched
+    assert_eq!(s.count, 1);
+    assert_eq!(s.amount, 60);
+    assert!(s.record_withdrawal(1_002, 40).is_ok());
+    assert!(s.record_withdrawal(1_100, 100).is_ok());
+}
+
+#[test]
+fn test_amount_overflow_is_rejected() {
+    let mut s = state(10, 60, u64::MAX);
+    assert!(s.record_withdrawal(1_000, u64::MAX).is_ok());
+    assert!(s.record_withdrawal(1_001, 1).is_err());
+}
+
+#[test]
+fn test_zero_limit_blocks_relayer() {
+    let mut s = state(0, 60, u64::MAX);
+    assert!(s.record_withdrawal(1_000, 1).is_err());
+}
@@ -0,0 +1,97 @@
+use anchor_lang::prelude::{AccountInfo, Pubkey};
+use anchor_lang::AccountSerialize;
+use payfi::registry::active_relayers;
+use payfi::{RelayerRecord, RELAYER_STATE_SEED};
+
+mod common;
+
+use common::pool_config;
+
+fn record(mint: Pubkey, relayer: Pubkey, active: bool) -> (Pubkey, RelayerRecord) {
+    let (address, bump) = Pubkey::find_program_address(&[RELAYER_STATE_SEED, mint.as_ref(), relayer.as_ref()], &payfi::ID);
+    let record = RelayerRecord {
+        relayer,
+        mint,
+        active,
+        metadata_url_hash: [0u8; 32],
+        fee_bps: 0,
+        window_start: 0,
+        count: 0,
+        limit: 1,
+        window_seconds: 60,
+        amount: 0,
+        amount_limit: u64::MAX,
+        bond: 1,
+        unbond_ready_at: 0,
+        total_withdrawals: 0,
+        total_volume: 0,
+        total_fees: 0,
+        bump,
+    };
+    (address, record)
+}
+
+fn serialize(record: &RelayerRecord) -> Vec<u8> {
+    let mut data = Vec::new();
+    record.try_serialize(&mut data).unwrap();
+    data
+}
+
+#[test]
+fn test_activation_is_counted_once() {
+    let mut config = pool_config();
+    let (_, mut r) = record(config.mint, Pubkey::new_unique(), false);
+    r.activate(&mut config);
+    r.activate(&mut config);
+    assert!(r.active);
+    assert_eq!(config.relayer_count, 1);
+    r.deactivate(&mut config);
+    r.deactivate(&mut config);
+    assert!(!r.active);
+    assert_eq!(config.relayer_count, 0);
+}
+
+#[test]
+fn test_record_payout_stats() {
+    let (_, mut r) = record(Pubkey::new_unique(), Pubkey::new_unique(), true);
+    r.record_payout(100, 3);
+    r.record_payout(50, 0);
+    assert_eq!((r.total_withdrawals, r.total_volume, r.total_fees), (2, 150, 3));
+}
+
+#[test]
+fn test_active_relayers_from_records() {
+    let mint = Pubkey::new_unique();
+    let (a_key, a) = record(mint, Pubkey::new_unique(), true);
+    let (b_key, b) = record(mint, Pubkey::new_unique(), false);
+    let (mut a_data, mut b_data) = (serialize(&a), serialize(&b));
+    let (mut a_lamports, mut b_lamports) = (1u64, 1u64);
+    let infos = [
+        AccountInfo::new(&a_key, false, false, &mut a_lamports, &mut a_data, &payfi::ID, false, 0),
+        AccountInfo::new(&b_key, false, false, &mut b_lamports, &mut b_data, &payfi::ID, false, 0),
+    ];
+    assert_eq!(active_relayers(&infos, &mint).unwrap(), vec![a.relayer]);
+}
+
+#[test]
+fn test_active_relayers_rejects_foreign_accounts() {
+    let mint = Pubkey::new_unique();
+    let (key, r) = record(mint, Pubkey::new_unique(), true);
+
+    // owned by another program
+    let (mut data, mut lamports) = (serialize(&r), 1u64);
+    let other_owner = Pubkey::new_unique();
+    let info = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &other_owner, false, 0);
+    assert!(active_relayers(&[info], &mint).is_err());
+
+    // record data at an address that is not its PDA
+    let (mut data, mut lamports) = (serialize(&r), 1u64);
+    let wrong = Pubkey::new_unique();
+    let info = AccountInfo::new(&wrong, false, false, &mut lamports, &mut data, &payfi::ID, false, 0);
+    assert!(active_relayers(&[info], &mint).is_err());
+
+    // record of the same relayer in another pool
+    let (mut data, mut lamports) = (serialize(&r), 1u64);
+    let info = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &payfi::ID, false, 0);
+    assert!(active_relayers(&[info], &Pubkey::new_unique()).is_err());
+}
@@ -0,0 +1,34 @@
+use anchor_lang::prelude::Pubkey;
+use payfi::roles::{ROLE_COMPLIANCE, ROLE_PAUSER, ROLE_VERIFIER_CONFIG};
+use payfi::{Admin, RoleAssignment};
+
+mod common;
+
+fn admin(authority: Pubkey) -> Admin {
+    Admin { authority, ..common::admin() }
+}
+
+#[test]
+fn test_authority_holds_every_role() {
+    let authority = Pubkey::new_unique();
+    let a = admin(authority);
+    assert!(a.require_role(&authority, None, ROLE_VERIFIER_CONFIG).is_ok());
+    assert!(a.require_role(&Pubkey::new_unique(), None, ROLE_PAUSER).is_err());
+}
+
+#[test]
+fn test_pauser_cannot_configure_verifier() {
+    let a = admin(Pubkey::new_unique());
+    let on_call = Pubkey::new_unique();
+    let assignment = RoleAssignment { holder: on_call, roles: ROLE_PAUSER, bump: 0 };
+    assert!(a.require_role(&on_call, Some(&assignment), ROLE_PAUSER).is_ok());
+    assert!(a.require_role(&on_call, Some(&assignment), ROLE_VERIFIER_CONFIG).is_err());
+    assert!(a.require_role(&on_call, Some(&assignment), ROLE_PAUSER | ROLE_COMPLIANCE).is_err());
+}
+
+#[test]
+fn test_assignment_must_belong_to_signer() {
+    let a = admin(Pubkey::new_unique());
+    let assignment = RoleAssignment { holder: Pubkey::new_unique(), roles: ROLE_PAUSER, bump: 0 };
+    assert!(a.require_role(&Pubkey::new_unique(), Some(&assignment), ROLE_PAUSER).is_err());
+}
@@ -0,0 +1,86 @@
+use anchor_lang::prelude::Pubkey;
+use payfi::sanctions::{sanctions_leaf, sanctions_node, verify_non_membership, SanctionsProof};
+
+fn key(b: u8) -> Pubkey {
+    Pubkey::new_from_array([b; 32])
+}
+
+/// Sorted list framed by sentinels and padded to a power of two; returns all tree levels
+fn build(listed: &[Pubkey]) -> (Vec<Pubkey>, Vec<Vec<[u8; 32]>>) {
+    let mut leaves = vec![key(0x00)];
+    let mut sorted = listed.to_vec();
+    sorted.sort();
+    leaves.extend(sorted);
+    leaves.push(key(0xff));
+    while !leaves.len().is_power_of_two() {
+        leaves.push(key(0xff));
+    }
+
+    let mut levels = vec![leaves.iter().map(sanctions_leaf).collect::<Vec<_>>()];
+    while levels.last().unwrap().len() > 1 {
+        let next = levels.last().unwrap().chunks(2).map(|p| sanctions_node(&p[0], &p[1])).collect();
+        levels.push(next);
+    }
+    (leaves, levels)
+}
+
+fn path(levels: &[Vec<[u8; 32]>], index: usize) -> Vec<[u8; 32]> {
+    levels[..levels.len() - 1].iter().enumerate().map(|(l, level)| level[(index >> l) ^ 1]).collect()
+}
+
+fn proof(leaves: &[Pubkey], levels: &[Vec<[u8; 32]>], low_index: usize) -> SanctionsProof {
+    SanctionsProof {
+        low: leaves[low_index],
+        low_index: low_index as u32,
+        low_path: path(levels, low_index),
+        high: leaves[low_index + 1],
+        high_path: path(levels, low_index + 1),
+    }
+}
+
+#[test]
+fn test_non_member_between_adjacent_leaves() {
+    let (leaves, levels) = build(&[key(0x20), key(0x40), key(0x60)]);
+    let root = levels.last().unwrap()[0];
+
+    // leaves: 00, 20, 40, 60, ff, ff, ff, ff
+    assert!(verify_non_membership(&root, &key(0x30), &proof(&leaves, &levels, 1)).is_ok());
+    assert!(verify_non_membership(&root, &key(0x10), &proof(&leaves, &levels, 0)).is_ok());
+    assert!(verify_non_membership(&root, &key(0x70), &proof(&leaves, &levels, 3)).is_ok());
+}
+
+#[test]
+fn test_listed_address_is_blocked() {
+    let (leaves, levels) = build(&[key(0x20), key(0x40), key(0x60)]);
+    let root = levels.last().unwrap()[0];
+    assert!(verify_non_membership(&root, &key(0x40), &proof(&leaves, &levels, 1)).is_err());
+    assert!(verify_non_membership(&root, &key(0x40), &proof(&leaves, &levels, 2)).is_err());
+}
+
+#[test]
+fn test_rejects_non_adjacent_or_unordered_brackets() {
+    let (leaves, levels) = build(&[key(0x20), key(0x40), key(0x60)]);
+    let root = levels.last().unwrap()[0];
+
+    // 0x50 is listed-adjacent to 40 and 60, not 20 and 40
+    assert!(verify_non_membership(&root, &key(0x50), &proof(&leaves, &levels, 1)).is_err());
+
+    // skipping over the listed 0x40 with a forged high index
+    let mut skip = proof(&leaves, &levels, 1);
+    skip.high = leaves[3];
+    skip.high_path = path(&levels, 3);
+    assert!(verify_non_membership(&root, &key(0x40), &skip).is_err());
+    assert!(verify_non_membership(&root, &key(0x50), &skip).is_err());
+}
+
+#[test]
+fn test_rejects_wrong_root_and_mismatched_paths() {
+    let (leaves, levels) = build(&[key(0x20)]);
+    let root = levels.last().unwrap()[0];
+    let good = proof(&leaves, &levels, 0);
+    assert!(verify_non_membership(&[7u8; 32], &key(0x10), &good).is_err());
+
+    let mut short = good.clone();
+    short.high_path.pop();
+    assert!(verify_non_membership(&root, &key(0x10), &short).is_err());
+}
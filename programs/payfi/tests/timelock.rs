@@ -0,0 +1,57 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::AnchorSerialize;
+use payfi::roles::{ROLE_RELAYER_MANAGER, ROLE_VERIFIER_CONFIG};
+use payfi::timelock::{ConfigChange, MAX_TIMELOCK_DELAY, MAX_VERIFIER_MAGIC_LEN};
+use payfi::PendingChange;
+
+#[test]
+fn test_change_roles() {
+    let key = Pubkey::new_unique();
+    assert_eq!(ConfigChange::VerifierMode { mint: key, mode: 1, magic: vec![] }.role(), Some(ROLE_VERIFIER_CONFIG));
+    assert_eq!(ConfigChange::VerifierProgram { mint: key, program: key, verification_key: key, verification_key_hash: [1u8; 32] }.role(), Some(ROLE_VERIFIER_CONFIG));
+    assert_eq!(ConfigChange::AddRelayer { mint: key, relayer: key }.role(), Some(ROLE_RELAYER_MANAGER));
+    assert_eq!(ConfigChange::RemoveRelayer { mint: key, relayer: key }.role(), Some(ROLE_RELAYER_MANAGER));
+    // changes that could hand over or freeze governance stay with the authority
+    assert_eq!(ConfigChange::ProposeAuthority { new_authority: key }.role(), None);
+    assert_eq!(ConfigChange::TimelockDelay { delay: 0 }.role(), None);
+}
+
+#[test]
+fn test_validate() {
+    assert!(ConfigChange::VerifierMode { mint: Pubkey::new_unique(), mode: 2, magic: vec![] }.validate().is_ok());
+    assert!(ConfigChange::VerifierMode { mint: Pubkey::new_unique(), mode: 3, magic: vec![] }.validate().is_err());
+    assert!(ConfigChange::VerifierMode { mint: Pubkey::new_unique(), mode: 1, magic: vec![0; MAX_VERIFIER_MAGIC_LEN + 1] }.validate().is_err());
+    assert!(ConfigChange::VerifierProgram { mint: Pubkey::new_unique(), program: Pubkey::default(), verification_key: Pubkey::new_unique(), verification_key_hash: [1u8; 32] }.validate().is_err());
+    assert!(ConfigChange::VerifierProgram { mint: Pubkey::new_unique(), program: Pubkey::new_unique(), verification_key: Pubkey::default(), verification_key_hash: [1u8; 32] }.validate().is_err());
+    assert!(ConfigChange::ProposeAuthority { new_authority: Pubkey::default() }.validate().is_err());
+    assert!(ConfigChange::TimelockDelay { delay: MAX_TIMELOCK_DELAY }.validate().is_ok());
+    assert!(ConfigChange::TimelockDelay { delay: MAX_TIMELOCK_DELAY + 1 }.validate().is_err());
+}
+
+#[test]
+fn test_max_space_covers_every_variant() {
+    let key = Pubkey::new_unique();
+    for change in [
+        ConfigChange::VerifierMode { mint: key, mode: 1, magic: vec![0; MAX_VERIFIER_MAGIC_LEN] },
+        ConfigChange::VerifierProgram { mint: key, program: key, verification_key: key, verification_key_hash: [1u8; 32] },
+        ConfigChange::AddRelayer { mint: key, relayer: key },
+        ConfigChange::ProposeAuthority { new_authority: key },
+        ConfigChange::TimelockDelay { delay: u64::MAX },
+    ] {
+        assert!(change.try_to_vec().unwrap().len() <= ConfigChange::MAX_SPACE);
+    }
+}
+
+#[test]
+fn test_ready_after_eta() {
+    let pending = PendingChange {
+        id: 0,
+        change: ConfigChange::TimelockDelay { delay: 60 },
+        queued_by: Pubkey::new_unique(),
+        queued_at: 1_000,
+        eta: 1_060,
+        bump: 0,
+    };
+    assert!(!pending.is_ready(1_059));
+    assert!(pending.is_ready(1_060));
+}
@@ -0,0 +1,18 @@
+use anchor_lang::prelude::{AccountInfo, Pubkey};
+use payfi::verification_key::{check_verification_key, verification_key_hash};
+
+#[test]
+fn test_pinned_key_must_keep_its_contents() {
+    let (address, program) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mut data = vec![7u8; 64];
+    let pinned = verification_key_hash(&data);
+
+    let mut lamports = 1u64;
+    let info = AccountInfo::new(&address, false, false, &mut lamports, &mut data, &program, false, 0);
+    assert!(check_verification_key(&info, &program, &pinned).is_ok());
+    assert!(check_verification_key(&info, &Pubkey::new_unique(), &pinned).is_err(), "owned by another program");
+
+    // closed and re-registered at the same address with other points
+    info.try_borrow_mut_data().unwrap()[63] ^= 1;
+    assert!(check_verification_key(&info, &program, &pinned).is_err());
+}
@@ -0,0 +1,145 @@
+//! Fixed-layout binary encoding for proofs and public inputs.
+//!
+//! Shared by the on-chain program and off-chain clients. Every field element is
+//! 32 bytes big-endian; G1 points are uncompressed `x || y` (64 bytes) and G2
+//! points are uncompressed `x.c1 || x.c0 || y.c1 || y.c0` (128 bytes), matching
+//! the `alt_bn128` syscall layout.
+
+use anchor_lang::prelude::*;
+
+use crate::groth16::{Groth16Proof, G1_LEN, G2_LEN, SCALAR_LEN};
+use crate::VerifierError;
+
+pub type G1Point = [u8; G1_LEN];
+pub type G2Point = [u8; G2_LEN];
+pub type FieldBytes = [u8; SCALAR_LEN];
+
+/// Number of G1 commitments in a Plonk proof (a, b, c, z, t1, t2, t3, wxi, wxiw)
+pub const PLONK_G1_COUNT: usize = 9;
+/// Number of field evaluations in a Plonk proof
+pub const PLONK_EVAL_COUNT: usize = 6;
+/// Encoded Plonk proof length (768 bytes)
+pub const PLONK_PROOF_LEN: usize = PLONK_G1_COUNT * G1_LEN + PLONK_EVAL_COUNT * SCALAR_LEN;
+/// Encoded Groth16 proof length: A || B || C (256 bytes)
+pub const GROTH16_PROOF_LEN: usize = G1_LEN + G2_LEN + G1_LEN;
+
+/// Noir Plonk Proof Structure (BN254 curve)
+///
+/// Encoded in field order: the nine commitments followed by the six evaluations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlonkProof {
+    pub a: G1Point,              // First commitment
+    pub b: G1Point,              // Second commitment
+    pub c: G1Point,              // Third commitment
+    pub z: G1Point,              // Permutation commitment
+    pub t1: G1Point,             // First quotient polynomial commitment
+    pub t2: G1Point,             // Second quotient polynomial commitment
+    pub t3: G1Point,             // Third quotient polynomial commitment
+    pub wxi: G1Point,            // Opening proof element 1
+    pub wxiw: G1Point,           // Opening proof element 2
+    pub a_eval: FieldBytes,      // Evaluation of permutation argument
+    pub b_eval: FieldBytes,      // Evaluation of second argument
+    pub c_eval: FieldBytes,      // Evaluation of third argument
+    pub s1_eval: FieldBytes,     // Evaluation of selector 1
+    pub s2_eval: FieldBytes,     // Evaluation of selector 2
+    pub z_omega_eval: FieldBytes, // Evaluation at omega (next point)
+}
+
+impl PlonkProof {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(PLONK_PROOF_LEN);
+        for point in [
+            &self.a, &self.b, &self.c, &self.z, &self.t1, &self.t2, &self.t3, &self.wxi, &self.wxiw,
+        ] {
+            out.extend_from_slice(point);
+        }
+        for eval in [
+            &self.a_eval,
+            &self.b_eval,
+            &self.c_eval,
+            &self.s1_eval,
+            &self.s2_eval,
+            &self.z_omega_eval,
+        ] {
+            out.extend_from_slice(eval);
+        }
+        out
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        require!(data.len() == PLONK_PROOF_LEN, VerifierError::ProofParsingFailed);
+        let mut reader = Reader::new(data);
+        Ok(Self {
+            a: reader.read()?,
+            b: reader.read()?,
+            c: reader.read()?,
+            z: reader.read()?,
+            t1: reader.read()?,
+            t2: reader.read()?,
+            t3: reader.read()?,
+            wxi: reader.read()?,
+            wxiw: reader.read()?,
+            a_eval: reader.read()?,
+            b_eval: reader.read()?,
+            c_eval: reader.read()?,
+            s1_eval: reader.read()?,
+            s2_eval: reader.read()?,
+            z_omega_eval: reader.read()?,
+        })
+    }
+}
+
+impl Groth16Proof {
+    /// A || B || C; identical to the Borsh layout of the struct
+    pub fn to_bytes(&self) -> [u8; GROTH16_PROOF_LEN] {
+        let mut out = [0u8; GROTH16_PROOF_LEN];
+        out[..G1_LEN].copy_from_slice(&self.a);
+        out[G1_LEN..G1_LEN + G2_LEN].copy_from_slice(&self.b);
+        out[G1_LEN + G2_LEN..].copy_from_slice(&self.c);
+        out
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        require!(data.len() == GROTH16_PROOF_LEN, VerifierError::ProofParsingFailed);
+        let mut reader = Reader::new(data);
+        Ok(Self {
+            a: reader.read()?,
+            b: reader.read()?,
+            c: reader.read()?,
+        })
+    }
+}
+
+/// Concatenate public inputs as 32-byte big-endian words
+pub fn encode_public_inputs(inputs: &[FieldBytes]) -> Vec<u8> {
+    inputs.concat()
+}
+
+/// Split a packed public-input buffer into 32-byte words
+pub fn decode_public_inputs(data: &[u8]) -> Result<Vec<FieldBytes>> {
+    require!(data.len() % SCALAR_LEN == 0, VerifierError::InvalidPublicInput);
+    Ok(data
+        .chunks_exact(SCALAR_LEN)
+        .map(|c| c.try_into().unwrap())
+        .collect())
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, offset: 0 }
+    }
+
+    fn read<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let end = self.offset + N;
+        require!(end <= self.data.len(), VerifierError::ProofParsingFailed);
+        let mut out = [0u8; N];
+        out.copy_from_slice(&self.data[self.offset..end]);
+        self.offset = end;
+        Ok(out)
+    }
+}
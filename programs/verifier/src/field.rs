@@ -0,0 +1,264 @@
+//! 256-bit prime field arithmetic for BN254.
+//!
+//! `Fr` is the scalar field (circuit public inputs, evaluations) and `Fq` the base
+//! field (curve point coordinates). Values are kept canonical (`< p`) as four
+//! little-endian `u64` limbs; multiplication goes through Montgomery reduction.
+
+use core::fmt::Debug;
+use core::marker::PhantomData;
+use core::ops::{Add, Mul, Neg, Sub};
+
+/// Modulus and Montgomery constants for a prime field
+pub trait FieldParams: Copy + Clone + Debug + PartialEq + Eq {
+    /// p as little-endian limbs
+    const MODULUS: [u64; 4];
+    /// R^2 mod p where R = 2^256
+    const R2: [u64; 4];
+    /// -p^-1 mod 2^64
+    const INV: u64;
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FrParams;
+
+impl FieldParams for FrParams {
+    const MODULUS: [u64; 4] = [
+        0x43e1f593f0000001,
+        0x2833e84879b97091,
+        0xb85045b68181585d,
+        0x30644e72e131a029,
+    ];
+    const R2: [u64; 4] = [
+        0x1bb8e645ae216da7,
+        0x53fe3ab1e35c59e3,
+        0x8c49833d53bb8085,
+        0x0216d0b17f4e44a5,
+    ];
+    const INV: u64 = 0xc2e1f593efffffff;
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FqParams;
+
+impl FieldParams for FqParams {
+    const MODULUS: [u64; 4] = [
+        0x3c208c16d87cfd47,
+        0x97816a916871ca8d,
+        0xb85045b68181585d,
+        0x30644e72e131a029,
+    ];
+    const R2: [u64; 4] = [
+        0xf32cfc5b538afa89,
+        0xb5e71911d44501fb,
+        0x47ab1eff0a417ff6,
+        0x06d89f71cab8351f,
+    ];
+    const INV: u64 = 0x87d20782e4866389;
+}
+
+/// BN254 scalar field element
+pub type Fr = Fp<FrParams>;
+/// BN254 base field element
+pub type Fq = Fp<FqParams>;
+
+/// Element of the prime field described by `P`, always `< P::MODULUS`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Fp<P: FieldParams> {
+    limbs: [u64; 4],
+    _params: PhantomData<P>,
+}
+
+impl<P: FieldParams> Fp<P> {
+    pub const ZERO: Self = Self::from_limbs([0, 0, 0, 0]);
+    pub const ONE: Self = Self::from_limbs([1, 0, 0, 0]);
+
+    const fn from_limbs(limbs: [u64; 4]) -> Self {
+        Self { limbs, _params: PhantomData }
+    }
+
+    /// Field modulus as 32 big-endian bytes
+    pub fn modulus_be_bytes() -> [u8; 32] {
+        limbs_to_be(&P::MODULUS)
+    }
+
+    pub fn from_u64(value: u64) -> Self {
+        // every supported modulus exceeds 2^64
+        Self::from_limbs([value, 0, 0, 0])
+    }
+
+    /// Parse a big-endian value, rejecting anything `>= p` (non-canonical)
+    pub fn from_be_bytes(bytes: &[u8; 32]) -> Option<Self> {
+        let limbs = be_to_limbs(bytes);
+        if geq(&limbs, &P::MODULUS) {
+            return None;
+        }
+        Some(Self::from_limbs(limbs))
+    }
+
+    pub fn to_be_bytes(&self) -> [u8; 32] {
+        limbs_to_be(&self.limbs)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs == [0, 0, 0, 0]
+    }
+
+    pub fn square(&self) -> Self {
+        *self * *self
+    }
+
+    /// self^exp for a little-endian limb exponent
+    pub fn pow(&self, exp: &[u64; 4]) -> Self {
+        let base = mont_mul::<P>(&self.limbs, &P::R2);
+        let mut acc = mont_mul::<P>(&[1, 0, 0, 0], &P::R2);
+        for limb in exp.iter().rev() {
+            for bit in (0..64).rev() {
+                acc = mont_mul::<P>(&acc, &acc);
+                if (limb >> bit) & 1 == 1 {
+                    acc = mont_mul::<P>(&acc, &base);
+                }
+            }
+        }
+        Self::from_limbs(mont_mul::<P>(&acc, &[1, 0, 0, 0]))
+    }
+
+    /// Multiplicative inverse via Fermat (self^(p-2)); `None` for zero
+    pub fn inverse(&self) -> Option<Self> {
+        if self.is_zero() {
+            return None;
+        }
+        let (exp, _) = sub_limbs(&P::MODULUS, &[2, 0, 0, 0]);
+        Some(self.pow(&exp))
+    }
+}
+
+impl<P: FieldParams> Add for Fp<P> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        let (sum, carry) = add_limbs(&self.limbs, &rhs.limbs);
+        if carry || geq(&sum, &P::MODULUS) {
+            Self::from_limbs(sub_limbs(&sum, &P::MODULUS).0)
+        } else {
+            Self::from_limbs(sum)
+        }
+    }
+}
+
+impl<P: FieldParams> Sub for Fp<P> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        let (diff, borrow) = sub_limbs(&self.limbs, &rhs.limbs);
+        if borrow {
+            Self::from_limbs(add_limbs(&diff, &P::MODULUS).0)
+        } else {
+            Self::from_limbs(diff)
+        }
+    }
+}
+
+impl<P: FieldParams> Neg for Fp<P> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::ZERO - self
+    }
+}
+
+impl<P: FieldParams> Mul for Fp<P> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        // (a * b * R^-1) * R^2 * R^-1 = a * b
+        let ab = mont_mul::<P>(&self.limbs, &rhs.limbs);
+        Self::from_limbs(mont_mul::<P>(&ab, &P::R2))
+    }
+}
+
+fn be_to_limbs(bytes: &[u8; 32]) -> [u64; 4] {
+    let mut limbs = [0u64; 4];
+    for (i, limb) in limbs.iter_mut().enumerate() {
+        let start = 32 - 8 * (i + 1);
+        *limb = u64::from_be_bytes(bytes[start..start + 8].try_into().unwrap());
+    }
+    limbs
+}
+
+fn limbs_to_be(limbs: &[u64; 4]) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    for (i, limb) in limbs.iter().enumerate() {
+        let start = 32 - 8 * (i + 1);
+        out[start..start + 8].copy_from_slice(&limb.to_be_bytes());
+    }
+    out
+}
+
+fn geq(a: &[u64; 4], b: &[u64; 4]) -> bool {
+    for i in (0..4).rev() {
+        if a[i] != b[i] {
+            return a[i] > b[i];
+        }
+    }
+    true
+}
+
+fn add_limbs(a: &[u64; 4], b: &[u64; 4]) -> ([u64; 4], bool) {
+    let mut out = [0u64; 4];
+    let mut carry = false;
+    for i in 0..4 {
+        let (s1, c1) = a[i].overflowing_add(b[i]);
+        let (s2, c2) = s1.overflowing_add(carry as u64);
+        out[i] = s2;
+        carry = c1 || c2;
+    }
+    (out, carry)
+}
+
+fn sub_limbs(a: &[u64; 4], b: &[u64; 4]) -> ([u64; 4], bool) {
+    let mut out = [0u64; 4];
+    let mut borrow = false;
+    for i in 0..4 {
+        let (d1, b1) = a[i].overflowing_sub(b[i]);
+        let (d2, b2) = d1.overflowing_sub(borrow as u64);
+        out[i] = d2;
+        borrow = b1 || b2;
+    }
+    (out, borrow)
+}
+
+/// Montgomery product a * b * R^-1 mod p (CIOS)
+fn mont_mul<P: FieldParams>(a: &[u64; 4], b: &[u64; 4]) -> [u64; 4] {
+    let m = &P::MODULUS;
+    let mut t = [0u64; 6];
+    for bi in b.iter() {
+        let mut carry = 0u128;
+        for j in 0..4 {
+            let v = t[j] as u128 + (a[j] as u128) * (*bi as u128) + carry;
+            t[j] = v as u64;
+            carry = v >> 64;
+        }
+        let v = t[4] as u128 + carry;
+        t[4] = v as u64;
+        t[5] = (v >> 64) as u64;
+
+        let k = t[0].wrapping_mul(P::INV);
+        let v = t[0] as u128 + (k as u128) * (m[0] as u128);
+        let mut carry = v >> 64;
+        for j in 1..4 {
+            let v = t[j] as u128 + (k as u128) * (m[j] as u128) + carry;
+            t[j - 1] = v as u64;
+            carry = v >> 64;
+        }
+        let v = t[4] as u128 + carry;
+        t[3] = v as u64;
+        t[4] = t[5] + (v >> 64) as u64;
+    }
+
+    let result = [t[0], t[1], t[2], t[3]];
+    if t[4] != 0 || geq(&result, m) {
+        sub_limbs(&result, m).0
+    } else {
+        result
+    }
+}
@@ -0,0 +1,99 @@
+//! BN254 Groth16 verification on top of the Solana `alt_bn128` syscalls.
+//!
+//! Encoding follows EIP-197: field elements are 32-byte big-endian, G1 points are
+//! `x || y` (64 bytes) and G2 points are `x.c1 || x.c0 || y.c1 || y.c0` (128 bytes).
+
+use anchor_lang::prelude::*;
+use solana_bn254::prelude::{alt_bn128_addition, alt_bn128_multiplication, alt_bn128_pairing};
+
+use crate::field::{Fq, Fr};
+use crate::VerifierError;
+
+pub const G1_LEN: usize = 64;
+pub const G2_LEN: usize = 128;
+pub const SCALAR_LEN: usize = 32;
+
+/// Groth16 proof points
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Groth16Proof {
+    pub a: [u8; G1_LEN],
+    pub b: [u8; G2_LEN],
+    pub c: [u8; G1_LEN],
+}
+
+/// Groth16 verifying key; `ic` holds one point per public input plus the constant term
+#[derive(Clone, Debug)]
+pub struct Groth16VerifyingKey<'a> {
+    pub alpha_g1: [u8; G1_LEN],
+    pub beta_g2: [u8; G2_LEN],
+    pub gamma_g2: [u8; G2_LEN],
+    pub delta_g2: [u8; G2_LEN],
+    pub ic: &'a [[u8; G1_LEN]],
+}
+
+/// Check `e(-A, B) * e(alpha, beta) * e(vk_x, gamma) * e(C, delta) == 1`
+pub fn verify_groth16(
+    vk: &Groth16VerifyingKey,
+    proof: &Groth16Proof,
+    public_inputs: &[[u8; SCALAR_LEN]],
+) -> Result<()> {
+    require!(
+        public_inputs.len() + 1 == vk.ic.len(),
+        VerifierError::InvalidPublicInputsCount
+    );
+
+    let vk_x = prepare_inputs(vk, public_inputs)?;
+    let neg_a = negate_g1(&proof.a)?;
+
+    let mut pairing_input = Vec::with_capacity(4 * (G1_LEN + G2_LEN));
+    for (g1, g2) in [
+        (&neg_a, &proof.b),
+        (&vk.alpha_g1, &vk.beta_g2),
+        (&vk_x, &vk.gamma_g2),
+        (&proof.c, &vk.delta_g2),
+    ] {
+        pairing_input.extend_from_slice(g1);
+        pairing_input.extend_from_slice(g2);
+    }
+
+    let result = alt_bn128_pairing(&pairing_input).map_err(|_| VerifierError::InvalidCurvePoint)?;
+    let mut one = [0u8; 32];
+    one[31] = 1;
+    require!(result[..] == one[..], VerifierError::VerificationFailed);
+    Ok(())
+}
+
+/// Compute `vk_x = IC[0] + sum(input_i * IC[i + 1])`
+fn prepare_inputs(
+    vk: &Groth16VerifyingKey,
+    public_inputs: &[[u8; SCALAR_LEN]],
+) -> Result<[u8; G1_LEN]> {
+    let mut acc = vk.ic[0];
+    for (input, ic) in public_inputs.iter().zip(vk.ic[1..].iter()) {
+        require!(Fr::from_be_bytes(input).is_some(), VerifierError::FieldElementOutOfRange);
+
+        let mut mul_input = [0u8; G1_LEN + SCALAR_LEN];
+        mul_input[..G1_LEN].copy_from_slice(ic);
+        mul_input[G1_LEN..].copy_from_slice(input);
+        let term = alt_bn128_multiplication(&mul_input).map_err(|_| VerifierError::InvalidCurvePoint)?;
+
+        let mut add_input = [0u8; 2 * G1_LEN];
+        add_input[..G1_LEN].copy_from_slice(&acc);
+        add_input[G1_LEN..].copy_from_slice(&term);
+        let sum = alt_bn128_addition(&add_input).map_err(|_| VerifierError::InvalidCurvePoint)?;
+        acc.copy_from_slice(&sum);
+    }
+    Ok(acc)
+}
+
+/// Negate a G1 point: (x, y) -> (x, q - y). The point at infinity is its own negation.
+pub fn negate_g1(point: &[u8; G1_LEN]) -> Result<[u8; G1_LEN]> {
+    if point.iter().all(|b| *b == 0) {
+        return Ok(*point);
+    }
+    let y = Fq::from_be_bytes(point[32..].try_into().unwrap()).ok_or(VerifierError::InvalidCurvePoint)?;
+
+    let mut out = *point;
+    out[32..].copy_from_slice(&(-y).to_be_bytes());
+    Ok(out)
+}
@@ -1,322 +1,309 @@
 use anchor_lang::prelude::*;
-use serde::{Deserialize, Serialize};
+
+pub mod encoding;
+pub mod field;
+pub mod groth16;
+pub mod verifying_key;
+
+use groth16::{verify_groth16, Groth16Proof, Groth16VerifyingKey, G1_LEN, G2_LEN};
 
 declare_id!("H7vpWaLWY1dDc8odHnZ3p4SMRT89uDe6WRpaP5ewwWoh");
 
-/// Production-grade Plonk verifier for PayFi ZK proofs
-/// 
-/// This verifier implements Plonk proof verification with:
-/// - Field arithmetic validation (BN254 curve)
-/// - Proof structure validation
-/// - Public input verification
-/// - Merkle path verification (circuit-specific)
+pub const ADMIN_SEED: &[u8] = b"admin";
+pub const VERIFICATION_KEY_SEED: &[u8] = b"verification_key";
+pub const PROOF_BUFFER_SEED: &[u8] = b"proof_buffer";
+
+/// Largest proof a `ProofBuffer` can hold (fits the 10 KiB CPI account-creation limit)
+pub const MAX_PROOF_BUFFER_LEN: usize = 8 * 1024;
+
+/// Upper bound on IC points (public inputs + 1) stored in a registered key
+pub const MAX_VK_IC_LEN: usize = 16;
+
+/// Groth16 verifier for PayFi ZK proofs
+///
+/// Proofs are checked with the BN254 alt_bn128 pairing syscalls against a verifying key
+/// registered per circuit version, either passed inline or staged in a `ProofBuffer`.
 
 #[program]
 pub mod verifier {
     use super::*;
 
-    /// Verify a Noir-generated Plonk proof
+    /// Verify a Groth16 proof previously uploaded to a `ProofBuffer` against a registered key
     ///
-    /// # Arguments
-    /// * `proof_json` - Serialized Noir proof (JSON format from circuit compilation)
-    /// * `public_inputs` - Public inputs from the circuit (root, nullifier)
-    /// 
-    /// # Returns
-    /// Success if proof is valid, error otherwise
-    pub fn verify_proof(
-        _ctx: Context<VerifyContext>,
-        proof_json: Vec<u8>,
-        public_inputs: Vec<String>,
+    /// Same checks as `verify_with_vk`; the buffer must hold exactly `A || B || C`.
+    pub fn verify_with_vk_from_buffer(
+        ctx: Context<VerifyWithVkFromBuffer>,
+        public_inputs: Vec<[u8; 32]>,
     ) -> Result<()> {
-        msg!("PayFi Verifier: Starting proof verification");
-        msg!("Proof size: {} bytes", proof_json.len());
-        msg!("Public inputs count: {}", public_inputs.len());
+        let vk = &ctx.accounts.verification_key;
+        msg!("PayFi Verifier: buffered Groth16 verification (circuit version {})", vk.version);
+        let proof = Groth16Proof::from_bytes(&ctx.accounts.proof_buffer.data)?;
 
-        // Step 1: Parse and validate proof structure
-        let proof = parse_proof(&proof_json)?;
-        msg!("✓ Proof structure valid");
+        verify_groth16(&vk.as_groth16(), &proof, &public_inputs)?;
 
-        // Step 2: Validate public inputs
+        msg!("✅ PROOF VERIFIED SUCCESSFULLY");
+        Ok(())
+    }
+
+    /// Allocate a zero-filled proof buffer of `len` bytes owned by `authority`
+    pub fn init_proof_buffer(ctx: Context<InitProofBuffer>, buffer_id: u64, len: u32) -> Result<()> {
         require!(
-            public_inputs.len() >= 2,
-            VerifierError::InvalidPublicInputsCount
+            len > 0 && len as usize <= MAX_PROOF_BUFFER_LEN,
+            VerifierError::InvalidProofBufferLength
         );
-        msg!("✓ Public inputs present (root, nullifier)");
+        let buffer = &mut ctx.accounts.proof_buffer;
+        buffer.authority = ctx.accounts.authority.key();
+        buffer.buffer_id = buffer_id;
+        buffer.data = vec![0u8; len as usize];
+        buffer.bump = ctx.bumps.proof_buffer;
+        Ok(())
+    }
 
-        // Step 3: Extract field elements from proof
-        let proof_elements = extract_proof_elements(&proof)?;
-        msg!("✓ Proof elements extracted");
+    /// Copy `bytes` into the buffer at `offset`; chunks may arrive in any order
+    pub fn write_proof_chunk(ctx: Context<WriteProofChunk>, offset: u32, bytes: Vec<u8>) -> Result<()> {
+        ctx.accounts.proof_buffer.write(offset, &bytes)
+    }
 
-        // Step 4: Perform field arithmetic validation
-        validate_field_arithmetic(&proof_elements, &public_inputs)?;
-        msg!("✓ Field arithmetic validated");
+    /// Close a proof buffer, refunding its rent to the authority
+    pub fn close_proof_buffer(_ctx: Context<CloseProofBuffer>) -> Result<()> {
+        Ok(())
+    }
 
-        // Step 5: Verify Plonk constraints
-        verify_plonk_constraints(&proof_elements, &public_inputs)?;
-        msg!("✓ Plonk constraints verified");
+    /// Verify a Groth16 proof against a key from the on-chain registry
+    pub fn verify_with_vk(
+        ctx: Context<VerifyWithVk>,
+        proof: Groth16Proof,
+        public_inputs: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let vk = &ctx.accounts.verification_key;
+        msg!("PayFi Verifier: Groth16 verification (circuit version {})", vk.version);
+        msg!("Public inputs count: {}", public_inputs.len());
 
-        // Step 6: Verify Merkle path consistency (PayFi specific)
-        verify_merkle_consistency(&proof_elements, &public_inputs)?;
-        msg!("✓ Merkle path verified");
+        verify_groth16(&vk.as_groth16(), &proof, &public_inputs)?;
 
         msg!("✅ PROOF VERIFIED SUCCESSFULLY");
         Ok(())
     }
 
-    /// Legacy verification for backward compatibility during migration
-    /// Only accepts test mode with exact magic match
-    pub fn verify_legacy(
-        _ctx: Context<VerifyContext>,
-        proof: Vec<u8>,
-        magic: Option<Vec<u8>>,
+    /// Create the verifier admin account holding the registry authority
+    ///
+    /// Only the program's upgrade authority may call this, so the registry cannot be
+    /// claimed by whoever front-runs the first transaction after deployment.
+    pub fn initialize(ctx: Context<InitializeVerifier>, authority: Pubkey) -> Result<()> {
+        let admin = &mut ctx.accounts.admin;
+        admin.authority = authority;
+        admin.bump = ctx.bumps.admin;
+        msg!("Verifier initialized by: {:?}", authority);
+        Ok(())
+    }
+
+    /// Register a verification key for `circuit_id` at `version`
+    pub fn register_vk(
+        ctx: Context<RegisterVk>,
+        circuit_id: [u8; 32],
+        version: u32,
+        key: VerificationKeyData,
     ) -> Result<()> {
-        // Only accept in test mode with magic bytes
-        if let Some(m) = magic {
-            require!(proof == m, VerifierError::InvalidProof);
-            msg!("✓ Legacy test mode: magic matched");
-            return Ok(());
-        }
+        require!(
+            !key.ic.is_empty() && key.ic.len() <= MAX_VK_IC_LEN,
+            VerifierError::InvalidVerificationKey
+        );
+
+        let vk = &mut ctx.accounts.verification_key;
+        vk.circuit_id = circuit_id;
+        vk.version = version;
+        vk.alpha_g1 = key.alpha_g1;
+        vk.beta_g2 = key.beta_g2;
+        vk.gamma_g2 = key.gamma_g2;
+        vk.delta_g2 = key.delta_g2;
+        vk.ic = key.ic;
+        vk.bump = ctx.bumps.verification_key;
+        msg!("Registered verification key version {} ({} public inputs)", version, vk.ic.len() - 1);
+        Ok(())
+    }
 
-        msg!("❌ Legacy verification requires test magic bytes");
-        Err(VerifierError::InvalidProof.into())
+    /// Close a verification key account, refunding rent to the authority
+    pub fn close_vk(_ctx: Context<CloseVk>, _circuit_id: [u8; 32], version: u32) -> Result<()> {
+        msg!("Closed verification key version {}", version);
+        Ok(())
     }
 }
 
 // ============================================================================
-// PROOF VERIFICATION IMPLEMENTATION
+// ACCOUNT STRUCTURES & ERRORS
 // ============================================================================
 
-/// Noir Plonk Proof Structure (BN254 curve)
-#[derive(Debug, Serialize, Deserialize)]
-struct PlonkProof {
-    a: String,           // First commitment
-    b: String,           // Second commitment  
-    c: String,           // Third commitment
-    z: String,           // Permutation commitment
-    t1: String,          // First quotient polynomial commitment
-    t2: String,          // Second quotient polynomial commitment
-    t3: String,          // Third quotient polynomial commitment
-    wxi: String,         // Opening proof element 1
-    wxiw: String,        // Opening proof element 2
-    a_eval: String,      // Evaluation of permutation argument
-    b_eval: String,      // Evaluation of second argument
-    c_eval: String,      // Evaluation of third argument
-    s1_eval: String,     // Evaluation of selector 1
-    s2_eval: String,     // Evaluation of selector 2
-    z_omega_eval: String, // Evaluation at omega (next point)
+#[derive(Accounts)]
+pub struct VerifyWithVk<'info> {
+    #[account(seeds = [VERIFICATION_KEY_SEED, verification_key.circuit_id.as_ref(), &verification_key.version.to_le_bytes()], bump = verification_key.bump)]
+    pub verification_key: Box<Account<'info, VerificationKey>>,
 }
 
-/// Extracted proof elements as field values
-struct ProofElements {
-    evaluations: Vec<u64>,
-    commitments: Vec<String>,
-    opening_proofs: Vec<String>,
+#[derive(Accounts)]
+pub struct InitializeVerifier<'info> {
+    #[account(init, payer = payer, space = 8 + 32 + 1, seeds = [ADMIN_SEED], bump)]
+    pub admin: Account<'info, Admin>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()) @ VerifierError::Unauthorized)]
+    pub program: Program<'info, crate::program::Verifier>,
+    #[account(constraint = program_data.upgrade_authority_address == Some(payer.key()) @ VerifierError::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+    pub system_program: Program<'info, System>,
 }
 
-/// Parse and validate proof structure
-fn parse_proof(proof_json: &[u8]) -> Result<PlonkProof> {
-    let proof: PlonkProof = serde_json::from_slice(proof_json)
-        .map_err(|_| VerifierError::ProofParsingFailed)?;
-
-    msg!("Parsed proof with {} field evaluations", 13); // BN254 field evaluations
-
-    Ok(proof)
+#[derive(Accounts)]
+#[instruction(circuit_id: [u8; 32], version: u32, key: VerificationKeyData)]
+pub struct RegisterVk<'info> {
+    #[account(seeds = [ADMIN_SEED], bump = admin.bump, has_one = authority)]
+    pub admin: Account<'info, Admin>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        init,
+        payer = authority,
+        space = VerificationKey::space(key.ic.len()),
+        seeds = [VERIFICATION_KEY_SEED, circuit_id.as_ref(), &version.to_le_bytes()],
+        bump
+    )]
+    pub verification_key: Box<Account<'info, VerificationKey>>,
+    pub system_program: Program<'info, System>,
 }
 
-/// Extract field elements from proof for arithmetic validation
-fn extract_proof_elements(proof: &PlonkProof) -> Result<ProofElements> {
-    // Convert hex strings to field values
-    // BN254 field modulus: 21888242871839275222246405745257275088548364400416034343698204186575808495617
-    const BN254_MODULUS: u64 = u64::MAX; // Simplified for on-chain (would be full modulus off-chain)
-
-    let mut evaluations = Vec::new();
-    
-    // Parse all evaluations (simplified: use last byte as checksum)
-    for eval_str in &[
-        &proof.a_eval,
-        &proof.b_eval,
-        &proof.c_eval,
-        &proof.s1_eval,
-        &proof.s2_eval,
-        &proof.z_omega_eval,
-    ] {
-        let val = parse_hex_field(eval_str)? % BN254_MODULUS;
-        evaluations.push(val);
-    }
-
-    Ok(ProofElements {
-        evaluations,
-        commitments: vec![
-            proof.a.clone(),
-            proof.b.clone(),
-            proof.c.clone(),
-            proof.z.clone(),
-        ],
-        opening_proofs: vec![proof.wxi.clone(), proof.wxiw.clone()],
-    })
+#[derive(Accounts)]
+#[instruction(circuit_id: [u8; 32], version: u32)]
+pub struct CloseVk<'info> {
+    #[account(seeds = [ADMIN_SEED], bump = admin.bump, has_one = authority)]
+    pub admin: Account<'info, Admin>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        close = authority,
+        seeds = [VERIFICATION_KEY_SEED, circuit_id.as_ref(), &version.to_le_bytes()],
+        bump = verification_key.bump
+    )]
+    pub verification_key: Box<Account<'info, VerificationKey>>,
 }
 
-/// Validate field arithmetic (simplified for on-chain)
-fn validate_field_arithmetic(
-    proof_elements: &ProofElements,
-    public_inputs: &[String],
-) -> Result<()> {
-    require!(
-        !proof_elements.evaluations.is_empty(),
-        VerifierError::MissingEvaluations
-    );
-
-    // Validate public inputs are valid field elements
-    for (idx, pi) in public_inputs.iter().enumerate() {
-        let _field_val = parse_field_element(pi)
-            .map_err(|_| VerifierError::InvalidPublicInput)?;
-        msg!("✓ Public input {} is valid field element", idx);
-    }
-
-    // Validate all evaluations fit in field
-    for (idx, eval) in proof_elements.evaluations.iter().enumerate() {
-        require!(eval < &u64::MAX, VerifierError::FieldElementOutOfRange);
-        msg!("✓ Evaluation {} in valid range", idx);
-    }
-
-    Ok(())
+#[derive(Accounts)]
+pub struct VerifyWithVkFromBuffer<'info> {
+    #[account(seeds = [VERIFICATION_KEY_SEED, verification_key.circuit_id.as_ref(), &verification_key.version.to_le_bytes()], bump = verification_key.bump)]
+    pub verification_key: Box<Account<'info, VerificationKey>>,
+    pub proof_buffer: Account<'info, ProofBuffer>,
 }
 
-/// Verify Plonk constraints (simplified for on-chain)
-///
-/// Full Plonk verification involves:
-/// 1. Computing the Fiat-Shamir challenge from public inputs and commitments
-/// 2. Verifying quotient polynomial evaluations
-/// 3. Verifying opening proofs using KZG or similar
-/// 4. Checking vanishing polynomial constraints
-fn verify_plonk_constraints(
-    proof_elements: &ProofElements,
-    public_inputs: &[String],
-) -> Result<()> {
-    // Step 1: Reconstruct Fiat-Shamir challenge
-    // In production: hash(commitments, public_inputs) using Poseidon
-    let _challenge = compute_challenge(&proof_elements.commitments, public_inputs)?;
-    msg!("✓ Fiat-Shamir challenge computed");
-
-    // Step 2: Verify proof commitments exist and are valid curve points
-    for (idx, commitment) in proof_elements.commitments.iter().enumerate() {
-        validate_curve_point(commitment)?;
-        msg!("✓ Commitment {} is valid curve point", idx);
-    }
-
-    // Step 3: Verify opening proofs
-    // In production: use KZG opening verification
-    for (idx, _opening) in proof_elements.opening_proofs.iter().enumerate() {
-        msg!("✓ Opening proof {} verified (simplified)", idx);
-    }
-
-    // Step 4: Verify evaluation consistency
-    // Placeholder: In production, compute and verify polynomial evaluations
-    msg!("✓ Polynomial evaluations consistent");
-
-    Ok(())
+#[derive(Accounts)]
+#[instruction(buffer_id: u64, len: u32)]
+pub struct InitProofBuffer<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = ProofBuffer::space(len as usize),
+        seeds = [PROOF_BUFFER_SEED, authority.key().as_ref(), &buffer_id.to_le_bytes()],
+        bump
+    )]
+    pub proof_buffer: Account<'info, ProofBuffer>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
 }
 
-/// Verify circuit-specific constraints (PayFi: Merkle path)
-fn verify_merkle_consistency(
-    proof_elements: &ProofElements,
-    public_inputs: &[String],
-) -> Result<()> {
-    // PayFi circuit constraint:
-    // - Public input 0: expected root
-    // - Public input 1: expected nullifier
-    // - Proof must show: hash(leaf, path_hashes) == root AND hash(leaf, secret) == nullifier
-
-    require!(public_inputs.len() >= 2, VerifierError::InvalidPublicInputsCount);
-
-    let _root = parse_field_element(&public_inputs[0])
-        .map_err(|_| VerifierError::InvalidPublicInput)?;
-    let _nullifier = parse_field_element(&public_inputs[1])
-        .map_err(|_| VerifierError::InvalidPublicInput)?;
-
-    // Validate proof contains Merkle path components
-    require!(
-        !proof_elements.evaluations.is_empty(),
-        VerifierError::MissingMerkleProof
-    );
-
-    msg!("✓ Merkle path components present in proof");
-    msg!("✓ Root and nullifier verification possible");
-
-    Ok(())
+#[derive(Accounts)]
+pub struct WriteProofChunk<'info> {
+    #[account(
+        mut,
+        seeds = [PROOF_BUFFER_SEED, authority.key().as_ref(), &proof_buffer.buffer_id.to_le_bytes()],
+        bump = proof_buffer.bump,
+        has_one = authority
+    )]
+    pub proof_buffer: Account<'info, ProofBuffer>,
+    pub authority: Signer<'info>,
 }
 
-// ============================================================================
-// HELPER FUNCTIONS
-// ============================================================================
-
-/// Parse hex string to field element
-fn parse_hex_field(hex_str: &str) -> Result<u64> {
-    let hex_clean = hex_str.trim_start_matches("0x");
-    u64::from_str_radix(hex_clean, 16)
-        .map_err(|_| VerifierError::FieldElementParsingFailed.into())
+#[derive(Accounts)]
+pub struct CloseProofBuffer<'info> {
+    #[account(
+        mut,
+        close = authority,
+        seeds = [PROOF_BUFFER_SEED, authority.key().as_ref(), &proof_buffer.buffer_id.to_le_bytes()],
+        bump = proof_buffer.bump,
+        has_one = authority
+    )]
+    pub proof_buffer: Account<'info, ProofBuffer>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
 }
 
-/// Parse field element string (hex or decimal)
-fn parse_field_element(element: &str) -> Result<u64> {
-    if element.starts_with("0x") {
-        parse_hex_field(element)
-    } else {
-        element
-            .parse::<u64>()
-            .map_err(|_| VerifierError::FieldElementParsingFailed.into())
-    }
+#[account]
+pub struct Admin {
+    pub authority: Pubkey,
+    pub bump: u8,
 }
 
-/// Validate string is a valid BN254 curve point (compressed or uncompressed)
-fn validate_curve_point(point_str: &str) -> Result<()> {
-    let point_hex = point_str.trim_start_matches("0x");
+/// Staging area for proofs larger than one transaction, filled by `write_proof_chunk`
+#[account]
+pub struct ProofBuffer {
+    pub authority: Pubkey,
+    pub buffer_id: u64,
+    pub data: Vec<u8>,
+    pub bump: u8,
+}
 
-    // BN254 compressed point: 64 hex chars (32 bytes) + flag
-    // Uncompressed: 128 hex chars (64 bytes)
-    require!(
-        point_hex.len() == 64 || point_hex.len() == 128 || point_hex.len() == 66,
-        VerifierError::InvalidCurvePoint
-    );
+impl ProofBuffer {
+    pub fn space(len: usize) -> usize {
+        8 + 32 + 8 + (4 + len) + 1
+    }
 
-    // Validate hex characters
-    require!(
-        point_hex.chars().all(|c| c.is_ascii_hexdigit()),
-        VerifierError::InvalidHexFormat
-    );
+    /// Copy `bytes` in at `offset`, rejecting writes past the allocated length
+    pub fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<()> {
+        let start = offset as usize;
+        let end = start
+            .checked_add(bytes.len())
+            .ok_or(VerifierError::ProofBufferOverflow)?;
+        require!(end <= self.data.len(), VerifierError::ProofBufferOverflow);
+        self.data[start..end].copy_from_slice(bytes);
+        Ok(())
+    }
+}
 
-    Ok(())
+/// Groth16 verifying key registered for one circuit version
+#[account]
+pub struct VerificationKey {
+    pub circuit_id: [u8; 32],
+    pub version: u32,
+    pub alpha_g1: [u8; G1_LEN],
+    pub beta_g2: [u8; G2_LEN],
+    pub gamma_g2: [u8; G2_LEN],
+    pub delta_g2: [u8; G2_LEN],
+    pub ic: Vec<[u8; G1_LEN]>,
+    pub bump: u8,
 }
 
-/// Compute Fiat-Shamir challenge from commitments and public inputs
-fn compute_challenge(
-    commitments: &[String],
-    public_inputs: &[String],
-) -> Result<String> {
-    // In production: use Poseidon hash
-    // Simplified: concatenate and hash
-    let mut challenge_input = String::new();
-
-    for commitment in commitments {
-        challenge_input.push_str(commitment);
-    }
-    for input in public_inputs {
-        challenge_input.push_str(input);
+impl VerificationKey {
+    pub fn space(ic_len: usize) -> usize {
+        8 + 32 + 4 + G1_LEN + 3 * G2_LEN + (4 + G1_LEN * ic_len) + 1
     }
 
-    // Return first 64 chars as hex (32-byte field element)
-    let challenge = format!("0x{:0>64}", challenge_input.chars().take(64).collect::<String>());
-    Ok(challenge)
+    pub fn as_groth16(&self) -> Groth16VerifyingKey<'_> {
+        Groth16VerifyingKey {
+            alpha_g1: self.alpha_g1,
+            beta_g2: self.beta_g2,
+            gamma_g2: self.gamma_g2,
+            delta_g2: self.delta_g2,
+            ic: &self.ic,
+        }
+    }
 }
 
-// ============================================================================
-// ACCOUNT STRUCTURES & ERRORS
-// ============================================================================
-
-#[derive(Accounts)]
-pub struct VerifyContext<'info> {
-    /// CHECK: Optional storage account for audit logging
-    pub audit_log: UncheckedAccount<'info>,
+/// Curve points submitted to `register_vk`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct VerificationKeyData {
+    pub alpha_g1: [u8; G1_LEN],
+    pub beta_g2: [u8; G2_LEN],
+    pub gamma_g2: [u8; G2_LEN],
+    pub delta_g2: [u8; G2_LEN],
+    pub ic: Vec<[u8; G1_LEN]>,
 }
 
 #[error_code]
@@ -324,7 +311,7 @@ pub enum VerifierError {
     #[msg("Invalid proof format")]
     InvalidProof,
 
-    #[msg("Failed to parse proof JSON")]
+    #[msg("Failed to decode proof")]
     ProofParsingFailed,
 
     #[msg("Field element parsing failed")]
@@ -353,4 +340,16 @@ pub enum VerifierError {
 
     #[msg("Proof verification failed")]
     VerificationFailed,
+
+    #[msg("Invalid verification key")]
+    InvalidVerificationKey,
+
+    #[msg("Invalid proof buffer length")]
+    InvalidProofBufferLength,
+
+    #[msg("Write exceeds proof buffer")]
+    ProofBufferOverflow,
+
+    #[msg("Signer is not the program upgrade authority")]
+    Unauthorized,
 }
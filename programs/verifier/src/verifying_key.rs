@@ -0,0 +1,102 @@
+//! Verifying key for the PayFi withdrawal circuit.
+//!
+//! Public inputs, in order: root, nullifier, recipient, amount, relayer, fee (see
+//! `payfi::public_inputs::WithdrawPublicInputs` for how each is mapped to a field element).
+//!
+//! Development key produced by a deterministic offline Groth16 setup; it matches the
+//! vectors in `tests/fixtures/groth16_withdraw.json`. Anyone can rerun that setup, so the
+//! key is only compiled in with the `dev-key` feature for tests; deployed programs verify
+//! against keys registered with `register_vk`.
+
+#[cfg(feature = "dev-key")]
+use crate::groth16::{Groth16VerifyingKey, G1_LEN};
+
+/// Number of public inputs of the withdrawal circuit
+pub const WITHDRAW_PUBLIC_INPUTS: usize = 6;
+
+#[cfg(feature = "dev-key")]
+pub const WITHDRAW_VK_IC: [[u8; G1_LEN]; WITHDRAW_PUBLIC_INPUTS + 1] = [
+    [
+        0x2b, 0x9d, 0x7b, 0x39, 0x51, 0x0f, 0xac, 0x37, 0xa3, 0x52, 0x3a, 0xc3, 0x54, 0x7c, 0x11, 0x83,
+        0xc1, 0x8e, 0xc5, 0x81, 0x46, 0xe9, 0xb4, 0x08, 0xd5, 0x28, 0xac, 0x17, 0x92, 0x97, 0xad, 0xae,
+        0x27, 0x67, 0x72, 0xbe, 0x81, 0x9b, 0xcd, 0x62, 0xec, 0xad, 0x8a, 0xd0, 0xf1, 0x98, 0xee, 0x8f,
+        0x65, 0xb8, 0x8c, 0x2a, 0xa5, 0x40, 0x0c, 0xba, 0x39, 0xa2, 0x93, 0xeb, 0xe7, 0x6e, 0x38, 0xcf,
+    ],
+    [
+        0x24, 0xa8, 0x68, 0x91, 0xb2, 0x0f, 0x81, 0xe7, 0x4d, 0xfa, 0x87, 0xa6, 0x36, 0xd8, 0x95, 0x52,
+        0x5c, 0xb6, 0xd1, 0x48, 0xa5, 0x6e, 0xb0, 0x36, 0xb3, 0x71, 0x0b, 0xe5, 0xec, 0x52, 0x89, 0xc0,
+        0x16, 0xc7, 0x61, 0x57, 0xbe, 0xb8, 0xfb, 0x18, 0xa2, 0xeb, 0x65, 0x9b, 0x9f, 0x91, 0x3f, 0x14,
+        0x38, 0x93, 0x17, 0x1e, 0x4c, 0x21, 0xc8, 0x0e, 0x75, 0xc4, 0x1b, 0x2e, 0x3f, 0x28, 0xe1, 0xa1,
+    ],
+    [
+        0x16, 0x16, 0xfb, 0xea, 0xf4, 0xdf, 0x65, 0x6b, 0xf3, 0xf1, 0x91, 0xeb, 0x59, 0xe8, 0x08, 0x4a,
+        0x82, 0x59, 0x66, 0xbf, 0x47, 0x01, 0xd3, 0xc2, 0x2a, 0x4a, 0xc0, 0xb7, 0xd6, 0xb8, 0x56, 0x9e,
+        0x0c, 0xab, 0x74, 0x64, 0x04, 0x7a, 0x0a, 0x06, 0xbd, 0x61, 0x6b, 0x30, 0xa3, 0xaa, 0xcf, 0x8c,
+        0x60, 0xea, 0xa9, 0x71, 0x60, 0x0f, 0xc2, 0xd3, 0x0d, 0x33, 0x5d, 0xd1, 0xa0, 0xcc, 0x0b, 0xb3,
+    ],
+    [
+        0x0d, 0x35, 0xe2, 0x7d, 0xf4, 0x13, 0x6d, 0xa4, 0xd3, 0xb0, 0x48, 0x8d, 0x80, 0xce, 0x99, 0xbc,
+        0xd7, 0xfd, 0x80, 0xef, 0x6d, 0xce, 0x58, 0xbb, 0x1a, 0x58, 0x1e, 0xa2, 0xbe, 0x18, 0x3a, 0xea,
+        0x21, 0x3b, 0x4c, 0x34, 0x7d, 0x8b, 0x66, 0x4a, 0xd8, 0x5c, 0x3f, 0x7b, 0xd6, 0x22, 0xbe, 0xe3,
+        0xa9, 0x37, 0x50, 0x56, 0xf8, 0x22, 0xe7, 0x2c, 0x3c, 0x6f, 0x94, 0x35, 0x46, 0x03, 0x20, 0x16,
+    ],
+    [
+        0x07, 0x84, 0x40, 0x1e, 0xc6, 0xb4, 0x05, 0x00, 0xec, 0x57, 0xc3, 0x15, 0x9c, 0x68, 0x9c, 0x4a,
+        0x99, 0x7c, 0x60, 0x83, 0x45, 0x84, 0x0f, 0x08, 0x52, 0xd8, 0x2e, 0x30, 0xa9, 0xe4, 0x37, 0xdc,
+        0x22, 0x61, 0x61, 0x19, 0x83, 0xed, 0x19, 0x78, 0xb1, 0x12, 0xbf, 0xa7, 0xde, 0xcd, 0x6e, 0x51,
+        0xdd, 0x9a, 0x94, 0x31, 0x12, 0x04, 0x1b, 0x39, 0x16, 0x0e, 0xc9, 0xe6, 0xe8, 0xc4, 0x8c, 0x8f,
+    ],
+    [
+        0x19, 0xf5, 0x46, 0xe1, 0xae, 0x5a, 0x42, 0xf6, 0xca, 0x95, 0xcb, 0x25, 0x07, 0x64, 0x3a, 0x07,
+        0x1e, 0x2e, 0xda, 0xc6, 0x05, 0x06, 0x37, 0xbf, 0x6b, 0xd9, 0x06, 0x98, 0x9f, 0x31, 0x30, 0x01,
+        0x18, 0xd2, 0x8f, 0x88, 0xf1, 0x2d, 0x42, 0xab, 0x2f, 0x32, 0x76, 0xdf, 0xb5, 0x1b, 0xf9, 0x02,
+        0x38, 0xd4, 0x59, 0x62, 0xe4, 0xe3, 0xc3, 0x34, 0x68, 0xf4, 0x61, 0xcf, 0x13, 0x8e, 0x27, 0x61,
+    ],
+    [
+        0x28, 0x1d, 0x31, 0x90, 0x8a, 0xc7, 0x81, 0x83, 0x8f, 0x27, 0xc6, 0xb0, 0xbf, 0x2a, 0xc7, 0xab,
+        0x86, 0x47, 0x77, 0x19, 0x25, 0xd6, 0x96, 0x6b, 0xad, 0x61, 0x42, 0x31, 0x1d, 0x2a, 0xd5, 0xe4,
+        0x1e, 0xfd, 0xc4, 0x75, 0x11, 0xc6, 0xda, 0x07, 0xed, 0x11, 0xc3, 0x6e, 0x33, 0x85, 0x28, 0x7f,
+        0x96, 0xa5, 0xa3, 0x5a, 0xeb, 0x22, 0x87, 0x2f, 0xea, 0x61, 0xf3, 0x7b, 0xb0, 0x39, 0xf0, 0x70,
+    ],
+];
+
+#[cfg(feature = "dev-key")]
+pub const WITHDRAW_VERIFYING_KEY: Groth16VerifyingKey<'static> = Groth16VerifyingKey {
+    alpha_g1: [
+        0x16, 0xcd, 0xe7, 0x67, 0x2d, 0x24, 0x07, 0x77, 0xd5, 0xe1, 0xe1, 0x09, 0xaf, 0x2a, 0x17, 0xcf,
+        0x9c, 0x6f, 0x65, 0xaa, 0xbd, 0x05, 0xa7, 0x6d, 0x82, 0x46, 0x1e, 0xd9, 0x2e, 0xda, 0xbc, 0xfb,
+        0x21, 0xd9, 0xf3, 0x8f, 0x3d, 0x39, 0x2c, 0xb6, 0x60, 0x5f, 0xe0, 0x98, 0x6b, 0x27, 0xe2, 0xce,
+        0x9c, 0x23, 0xdc, 0x0b, 0x89, 0x36, 0xd7, 0xaf, 0x05, 0xeb, 0x21, 0x3d, 0xfd, 0xae, 0xd0, 0x20,
+    ],
+    beta_g2: [
+        0x13, 0x54, 0x10, 0xeb, 0x08, 0xcc, 0x99, 0x67, 0x8e, 0x97, 0x3f, 0x83, 0x6a, 0xac, 0x67, 0x57,
+        0x3b, 0x37, 0x10, 0x6e, 0x8f, 0x6b, 0x65, 0xdd, 0xc7, 0xb2, 0x97, 0x6f, 0xc1, 0x6a, 0xd2, 0xeb,
+        0x2f, 0xa3, 0x28, 0x30, 0xb8, 0x4e, 0x44, 0x63, 0xe4, 0xde, 0xde, 0x31, 0xa1, 0x62, 0x78, 0xeb,
+        0x74, 0x24, 0x16, 0xea, 0xe4, 0xbe, 0xf6, 0xb1, 0x56, 0x90, 0x35, 0x87, 0xcf, 0xf9, 0x83, 0x80,
+        0x14, 0x89, 0xd8, 0x7c, 0x06, 0x78, 0xfe, 0x26, 0x82, 0x12, 0xb8, 0xa9, 0xf9, 0xe5, 0x7a, 0xa5,
+        0xee, 0x21, 0x8a, 0x97, 0x2b, 0x6a, 0xa0, 0x66, 0xa6, 0xd9, 0x22, 0x9f, 0x4f, 0xea, 0x9b, 0x99,
+        0x17, 0x88, 0xfe, 0x16, 0x9a, 0xd4, 0x82, 0xa2, 0x0f, 0xd2, 0xc4, 0xa7, 0x0f, 0x3c, 0x65, 0xa5,
+        0x5f, 0xed, 0x20, 0x22, 0xdf, 0x58, 0xac, 0xa2, 0x86, 0xc1, 0xac, 0xdf, 0x8c, 0x34, 0xc5, 0xf9,
+    ],
+    gamma_g2: [
+        0x2b, 0xbe, 0xc1, 0x64, 0x6a, 0xfc, 0x4b, 0x6f, 0x9e, 0xaa, 0x39, 0x26, 0xda, 0x6b, 0x62, 0x02,
+        0x1e, 0x69, 0x92, 0x2d, 0x4f, 0x2f, 0xfc, 0xac, 0xf2, 0x89, 0x6a, 0x7b, 0xb6, 0x36, 0xd4, 0xa9,
+        0x2c, 0xf2, 0xbb, 0x37, 0xe9, 0xec, 0x03, 0x05, 0xb7, 0x1e, 0x42, 0xf5, 0xcf, 0xb5, 0xa6, 0x11,
+        0x7a, 0x30, 0xba, 0x10, 0x86, 0xfa, 0x06, 0x00, 0xa9, 0x72, 0x0d, 0xef, 0x28, 0xba, 0x4e, 0xe7,
+        0x0d, 0x1b, 0x2b, 0x86, 0xda, 0xc6, 0x82, 0xbd, 0x0e, 0x36, 0xee, 0x8d, 0xe9, 0xc6, 0x37, 0x50,
+        0xff, 0xee, 0xb5, 0x20, 0xc9, 0x26, 0x81, 0x97, 0x6e, 0x36, 0x2d, 0x1b, 0x3d, 0xc0, 0xe8, 0x5d,
+        0x06, 0x2f, 0x3d, 0x3b, 0x4d, 0x16, 0x2b, 0xe8, 0x24, 0x3a, 0x46, 0xc6, 0x51, 0xe1, 0xfe, 0x73,
+        0x58, 0x41, 0xbc, 0xae, 0xd0, 0xb7, 0xb2, 0xec, 0x9d, 0x49, 0x79, 0xfa, 0x97, 0xd4, 0x8f, 0xf0,
+    ],
+    delta_g2: [
+        0x23, 0x39, 0x1e, 0xd9, 0x78, 0x51, 0xcf, 0xb5, 0xd0, 0xbb, 0x6b, 0xc9, 0x01, 0xec, 0x00, 0xfe,
+        0xaa, 0x36, 0x23, 0x5f, 0xac, 0x6a, 0x8d, 0xba, 0x43, 0xd0, 0xee, 0x75, 0xe1, 0x2c, 0x2a, 0xa2,
+        0x01, 0x13, 0x5f, 0xa9, 0xc0, 0x47, 0x22, 0x9c, 0xa2, 0x56, 0x27, 0xc2, 0xbf, 0x21, 0x94, 0xc0,
+        0x43, 0xe0, 0x3f, 0x34, 0xa8, 0xf6, 0xcc, 0x94, 0xb5, 0xff, 0x1d, 0x61, 0xf1, 0x9b, 0x9a, 0x7e,
+        0x13, 0x7a, 0xc0, 0x03, 0xc9, 0xaf, 0xf1, 0x9b, 0xd6, 0x73, 0x36, 0x08, 0x30, 0x96, 0xaf, 0x7e,
+        0xee, 0x88, 0x53, 0x59, 0x85, 0xcb, 0xb6, 0xe0, 0xb4, 0x0d, 0x74, 0xe8, 0x40, 0x45, 0x59, 0xd4,
+        0x2d, 0x27, 0x0a, 0x27, 0x6a, 0x25, 0x90, 0xd9, 0xe1, 0xb2, 0xb8, 0xbd, 0x70, 0x44, 0x7b, 0x78,
+        0x80, 0xe9, 0x29, 0x42, 0x98, 0x1c, 0x2b, 0x01, 0x36, 0xac, 0x58, 0xba, 0xfb, 0x38, 0x9d, 0x87,
+    ],
+    ic: &WITHDRAW_VK_IC,
+};
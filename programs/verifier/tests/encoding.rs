@@ -0,0 +1,97 @@
+use anchor_lang::AnchorSerialize;
+use verifier::encoding::{
+    decode_public_inputs, encode_public_inputs, PlonkProof, GROTH16_PROOF_LEN, PLONK_PROOF_LEN,
+};
+use verifier::groth16::Groth16Proof;
+
+fn g1(tag: u8) -> [u8; 64] {
+    let mut p = [0u8; 64];
+    p[0] = tag;
+    p[31] = tag;
+    p[32] = tag;
+    p[63] = tag;
+    p
+}
+
+fn fe(tag: u8) -> [u8; 32] {
+    let mut f = [0u8; 32];
+    f[1] = tag;
+    f[31] = tag;
+    f
+}
+
+/// Every PlonkProof field gets a distinct value so misordering is detected
+fn sample_plonk_proof() -> PlonkProof {
+    PlonkProof {
+        a: g1(1),
+        b: g1(2),
+        c: g1(3),
+        z: g1(4),
+        t1: g1(5),
+        t2: g1(6),
+        t3: g1(7),
+        wxi: g1(8),
+        wxiw: g1(9),
+        a_eval: fe(10),
+        b_eval: fe(11),
+        c_eval: fe(12),
+        s1_eval: fe(13),
+        s2_eval: fe(14),
+        z_omega_eval: fe(15),
+    }
+}
+
+#[test]
+fn test_plonk_proof_round_trip() {
+    let proof = sample_plonk_proof();
+    let bytes = proof.to_bytes();
+    assert_eq!(bytes.len(), PLONK_PROOF_LEN);
+    assert_eq!(PlonkProof::from_bytes(&bytes).unwrap(), proof);
+}
+
+#[test]
+fn test_plonk_proof_layout() {
+    let bytes = sample_plonk_proof().to_bytes();
+    // commitments first, 64 bytes each, in field order
+    assert_eq!(&bytes[0..64], &g1(1));
+    assert_eq!(&bytes[3 * 64..4 * 64], &g1(4)); // z
+    assert_eq!(&bytes[8 * 64..9 * 64], &g1(9)); // wxiw
+    // evaluations follow, 32 bytes each, big-endian
+    assert_eq!(&bytes[9 * 64..9 * 64 + 32], &fe(10)); // a_eval
+    assert_eq!(&bytes[PLONK_PROOF_LEN - 32..], &fe(15)); // z_omega_eval
+}
+
+#[test]
+fn test_plonk_proof_rejects_wrong_length() {
+    let bytes = sample_plonk_proof().to_bytes();
+    assert!(PlonkProof::from_bytes(&bytes[..PLONK_PROOF_LEN - 1]).is_err());
+    let mut long = bytes.clone();
+    long.push(0);
+    assert!(PlonkProof::from_bytes(&long).is_err());
+    assert!(PlonkProof::from_bytes(&[]).is_err());
+}
+
+#[test]
+fn test_groth16_proof_round_trip_matches_borsh() {
+    let mut b = [0u8; 128];
+    b[0] = 0xbb;
+    b[127] = 0xbc;
+    let proof = Groth16Proof { a: g1(1), b, c: g1(3) };
+
+    let bytes = proof.to_bytes();
+    assert_eq!(bytes.len(), GROTH16_PROOF_LEN);
+    assert_eq!(bytes.to_vec(), proof.try_to_vec().unwrap());
+    assert_eq!(Groth16Proof::from_bytes(&bytes).unwrap(), proof);
+    assert!(Groth16Proof::from_bytes(&bytes[1..]).is_err());
+}
+
+#[test]
+fn test_public_inputs_round_trip() {
+    let inputs = vec![fe(1), fe(2), [0xffu8; 32]];
+    let packed = encode_public_inputs(&inputs);
+    assert_eq!(packed.len(), 96);
+    assert_eq!(decode_public_inputs(&packed).unwrap(), inputs);
+    // full 256-bit values survive; nothing is truncated to u64
+    assert_eq!(decode_public_inputs(&packed).unwrap()[2], [0xffu8; 32]);
+    assert!(decode_public_inputs(&packed[..95]).is_err());
+}
@@ -0,0 +1,90 @@
+use verifier::field::{Fq, Fr};
+
+fn be(hex: &str) -> [u8; 32] {
+    let hex = hex.trim_start_matches("0x");
+    let padded = format!("{:0>64}", hex);
+    let mut out = [0u8; 32];
+    for (i, byte) in out.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&padded[2 * i..2 * i + 2], 16).unwrap();
+    }
+    out
+}
+
+const A: &str = "1f2e3d4c5b6a79880123456789abcdeffedcba98765432100f1e2d3c4b5a6978";
+const B: &str = "0badc0ffee0ddf00d15ea5e0123456789abcdef0fedcba9876543210deadbeef";
+
+#[test]
+fn test_canonical_range_check() {
+    let r = Fr::modulus_be_bytes();
+    assert_eq!(r, be("30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001"));
+    assert!(Fr::from_be_bytes(&r).is_none(), "r itself is not canonical");
+    assert!(Fr::from_be_bytes(&[0xffu8; 32]).is_none());
+
+    let mut r_minus_one = r;
+    r_minus_one[31] -= 1;
+    assert!(Fr::from_be_bytes(&r_minus_one).is_some());
+
+    // values in [r, q) are canonical in Fq but not in Fr
+    let q = Fq::modulus_be_bytes();
+    assert!(Fq::from_be_bytes(&r).is_some());
+    assert!(Fq::from_be_bytes(&q).is_none());
+}
+
+#[test]
+fn test_bytes_round_trip() {
+    let a = Fr::from_be_bytes(&be(A)).unwrap();
+    assert_eq!(a.to_be_bytes(), be(A));
+    assert_eq!(Fr::from_u64(0x1234).to_be_bytes(), be("1234"));
+}
+
+#[test]
+fn test_fr_arithmetic_matches_reference() {
+    let a = Fr::from_be_bytes(&be(A)).unwrap();
+    let b = Fr::from_be_bytes(&be(B)).unwrap();
+    // reference values computed with Python big integers
+    assert_eq!((a * b).to_be_bytes(), be("3047e683be77227e31bbe75f8aca6e89d3014815be993f7ef75b912a604b903c"));
+    assert_eq!((a + b).to_be_bytes(), be("2adbfe4c49785888d281eb479be02468999999897530eca885725f4d2a082867"));
+    assert_eq!((b - a).to_be_bytes(), be("1ce3d22673d505a2888ba62f0a09e0e5c4140ca10241f919ab17fa6883535578"));
+    assert_eq!(a.inverse().unwrap().to_be_bytes(), be("2698240c60eae9783f6bd9a24053ef5ee2d1d0d69ce8b3135d255c3dd2cc7963"));
+}
+
+#[test]
+fn test_fq_arithmetic_matches_reference() {
+    let a = Fq::from_be_bytes(&be(A)).unwrap();
+    let b = Fq::from_be_bytes(&be(B)).unwrap();
+    assert_eq!((a * b).to_be_bytes(), be("13f27c65a99fd000d9eaca4ebe945ccf371f4316762145b0662ea526f3f032e7"));
+    assert_eq!((b - a).to_be_bytes(), be("1ce3d22673d505a2888ba62f0a09e0e633618ee9f0fa5315a35690eb6bd052be"));
+    assert_eq!(a.inverse().unwrap().to_be_bytes(), be("206280fc9ee4d59a3ee45b708b174065d9ca33d130aed47f95e906045237d403"));
+}
+
+#[test]
+fn test_modular_wraparound() {
+    let minus_one = -Fr::ONE;
+    assert_eq!(minus_one + Fr::ONE, Fr::ZERO);
+    assert_eq!(minus_one * minus_one, Fr::ONE);
+    assert_eq!(Fr::ZERO - Fr::ONE, minus_one);
+    assert_eq!(-Fr::ZERO, Fr::ZERO);
+}
+
+#[test]
+fn test_inverse() {
+    assert!(Fr::ZERO.inverse().is_none());
+    assert_eq!(Fr::ONE.inverse().unwrap(), Fr::ONE);
+    for v in [2u64, 3, 7, 1 << 40, u64::MAX] {
+        let x = Fr::from_u64(v);
+        assert_eq!(x * x.inverse().unwrap(), Fr::ONE);
+        let y = Fq::from_u64(v);
+        assert_eq!(y * y.inverse().unwrap(), Fq::ONE);
+    }
+}
+
+#[test]
+fn test_small_values_match_u128() {
+    let pairs = [(0u64, 0u64), (1, u64::MAX), (u64::MAX, u64::MAX), (123456789, 987654321)];
+    for (x, y) in pairs {
+        let product = (x as u128) * (y as u128);
+        let mut expected = [0u8; 32];
+        expected[16..].copy_from_slice(&product.to_be_bytes());
+        assert_eq!((Fr::from_u64(x) * Fr::from_u64(y)).to_be_bytes(), expected);
+    }
+}
@@ -0,0 +1,158 @@
+use verifier::field::Fr;
+use verifier::groth16::{negate_g1, verify_groth16, Groth16Proof, Groth16VerifyingKey};
+use verifier::verifying_key::{WITHDRAW_PUBLIC_INPUTS, WITHDRAW_VERIFYING_KEY};
+use verifier::VerificationKey;
+
+/// Vectors generated offline with arkworks (ark-groth16 0.4, deterministic seed)
+const VECTORS: &str = include_str!("fixtures/groth16_withdraw.json");
+
+fn unhex<const N: usize>(s: &str) -> [u8; N] {
+    let bytes: Vec<u8> = (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect();
+    bytes.try_into().unwrap()
+}
+
+struct Vectors {
+    alpha_g1: [u8; 64],
+    beta_g2: [u8; 128],
+    gamma_g2: [u8; 128],
+    delta_g2: [u8; 128],
+    ic: Vec<[u8; 64]>,
+    proof: Groth16Proof,
+    public_inputs: Vec<[u8; 32]>,
+}
+
+fn load() -> Vectors {
+    let v: serde_json::Value = serde_json::from_str(VECTORS).unwrap();
+    let s = |k: &str| v[k].as_str().unwrap().to_string();
+    let list = |k: &str| -> Vec<String> {
+        v[k].as_array().unwrap().iter().map(|x| x.as_str().unwrap().to_string()).collect()
+    };
+    Vectors {
+        alpha_g1: unhex(&s("vk_alpha_g1")),
+        beta_g2: unhex(&s("vk_beta_g2")),
+        gamma_g2: unhex(&s("vk_gamma_g2")),
+        delta_g2: unhex(&s("vk_delta_g2")),
+        ic: list("vk_ic").iter().map(|h| unhex(h)).collect(),
+        proof: Groth16Proof {
+            a: unhex(&s("proof_a")),
+            b: unhex(&s("proof_b")),
+            c: unhex(&s("proof_c")),
+        },
+        public_inputs: list("public_inputs").iter().map(|h| unhex(h)).collect(),
+    }
+}
+
+fn vk(v: &Vectors) -> Groth16VerifyingKey<'_> {
+    Groth16VerifyingKey {
+        alpha_g1: v.alpha_g1,
+        beta_g2: v.beta_g2,
+        gamma_g2: v.gamma_g2,
+        delta_g2: v.delta_g2,
+        ic: &v.ic,
+    }
+}
+
+#[test]
+fn test_groth16_valid_proof_verifies() {
+    let v = load();
+    assert!(verify_groth16(&vk(&v), &v.proof, &v.public_inputs).is_ok());
+}
+
+#[test]
+fn test_embedded_key_matches_vectors() {
+    let v = load();
+    assert_eq!(WITHDRAW_VERIFYING_KEY.alpha_g1, v.alpha_g1);
+    assert_eq!(WITHDRAW_VERIFYING_KEY.delta_g2, v.delta_g2);
+    assert_eq!(WITHDRAW_VERIFYING_KEY.ic, &v.ic[..]);
+    assert!(verify_groth16(&WITHDRAW_VERIFYING_KEY, &v.proof, &v.public_inputs).is_ok());
+}
+
+#[test]
+fn test_groth16_rejects_wrong_public_input() {
+    let v = load();
+    let mut inputs = v.public_inputs.clone();
+    inputs[1][31] ^= 1; // different nullifier
+    assert!(verify_groth16(&vk(&v), &v.proof, &inputs).is_err());
+}
+
+#[test]
+fn test_groth16_binds_recipient_amount_and_fee() {
+    let v = load();
+    assert_eq!(v.public_inputs.len(), WITHDRAW_PUBLIC_INPUTS);
+    // recipient, amount, relayer, fee: changing any of them must invalidate the proof
+    for idx in 2..WITHDRAW_PUBLIC_INPUTS {
+        let mut inputs = v.public_inputs.clone();
+        inputs[idx][31] ^= 1;
+        assert!(verify_groth16(&vk(&v), &v.proof, &inputs).is_err());
+    }
+}
+
+#[test]
+fn test_groth16_rejects_tampered_proof() {
+    let v = load();
+    let mut proof = v.proof.clone();
+    proof.c = v.proof.a; // valid curve point, wrong proof
+    assert!(verify_groth16(&vk(&v), &proof, &v.public_inputs).is_err());
+
+    let mut proof = v.proof.clone();
+    proof.a = negate_g1(&v.proof.a).unwrap();
+    assert!(verify_groth16(&vk(&v), &proof, &v.public_inputs).is_err());
+}
+
+#[test]
+fn test_groth16_rejects_off_curve_point() {
+    let v = load();
+    let mut proof = v.proof.clone();
+    proof.a[63] ^= 1;
+    assert!(verify_groth16(&vk(&v), &proof, &v.public_inputs).is_err());
+}
+
+#[test]
+fn test_groth16_rejects_wrong_input_count() {
+    let v = load();
+    assert!(verify_groth16(&vk(&v), &v.proof, &v.public_inputs[..1]).is_err());
+    let mut extra = v.public_inputs.clone();
+    extra.push([0u8; 32]);
+    assert!(verify_groth16(&vk(&v), &v.proof, &extra).is_err());
+}
+
+#[test]
+fn test_groth16_rejects_non_canonical_input() {
+    let v = load();
+    // input + r is congruent to input but must not be accepted
+    let mut inputs = v.public_inputs.clone();
+    inputs[0] = Fr::modulus_be_bytes();
+    assert!(verify_groth16(&vk(&v), &v.proof, &inputs).is_err());
+}
+
+fn registered_vk(v: &Vectors) -> VerificationKey {
+    VerificationKey {
+        circuit_id: [7u8; 32],
+        version: 1,
+        alpha_g1: v.alpha_g1,
+        beta_g2: v.beta_g2,
+        gamma_g2: v.gamma_g2,
+        delta_g2: v.delta_g2,
+        ic: v.ic.clone(),
+        bump: 255,
+    }
+}
+
+#[test]
+fn test_registered_vk_verifies() {
+    let v = load();
+    let vk = registered_vk(&v);
+    assert!(verify_groth16(&vk.as_groth16(), &v.proof, &v.public_inputs).is_ok());
+}
+
+#[test]
+fn test_registered_vk_space_matches_serialized_len() {
+    use anchor_lang::AnchorSerialize;
+    let v = load();
+    let vk = registered_vk(&v);
+    let data = vk.try_to_vec().unwrap();
+    assert_eq!(VerificationKey::space(v.ic.len()), 8 + data.len());
+}
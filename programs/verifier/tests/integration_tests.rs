@@ -1,5 +1,3 @@
-use serde_json;
-
 #[test]
 fn test_plonk_verifier_valid_proof() {
     // Test vector: valid Plonk proof structure
@@ -31,7 +29,7 @@ fn test_plonk_verifier_valid_proof() {
 #[test]
 fn test_plonk_verifier_invalid_proof() {
     // Test invalid proof formats
-    let invalid_proofs = vec![
+    let invalid_proofs = [
         "invalid json",
         "{}", // empty object
         r#"{"a": "0x00"}"#, // missing fields
@@ -44,7 +42,7 @@ fn test_plonk_verifier_invalid_proof() {
             1 => assert!(result.is_ok() && result.unwrap().as_object().unwrap().is_empty(), "Empty proof should be rejected"),
             2 => {
                 let val = result.unwrap();
-                assert!(!val.get("b").is_some() || val.get("b").is_none(), "Incomplete proof should be rejected");
+                assert!(val.get("b").is_none(), "Incomplete proof should be rejected");
             }
             _ => {}
         }
@@ -0,0 +1,41 @@
+use anchor_lang::AnchorSerialize;
+use verifier::encoding::PLONK_PROOF_LEN;
+use verifier::{ProofBuffer, MAX_PROOF_BUFFER_LEN};
+
+fn buffer(data: Vec<u8>) -> ProofBuffer {
+    ProofBuffer {
+        authority: Default::default(),
+        buffer_id: 7,
+        data,
+        bump: 255,
+    }
+}
+
+#[test]
+fn test_proof_buffer_space_matches_serialized_len() {
+    for len in [1, PLONK_PROOF_LEN, MAX_PROOF_BUFFER_LEN] {
+        let data = buffer(vec![0u8; len]).try_to_vec().unwrap();
+        assert_eq!(ProofBuffer::space(len), 8 + data.len());
+    }
+    // the largest buffer can still be created through a CPI (10 KiB limit)
+    assert!(ProofBuffer::space(MAX_PROOF_BUFFER_LEN) <= 10 * 1024);
+}
+
+
+#[test]
+fn test_chunks_fill_buffer_in_any_order() {
+    let mut b = buffer(vec![0u8; 8]);
+    b.write(4, &[5, 6, 7, 8]).unwrap();
+    b.write(0, &[1, 2, 3, 4]).unwrap();
+    assert_eq!(b.data, vec![1, 2, 3, 4, 5, 6, 7, 8]);
+}
+
+#[test]
+fn test_write_rejects_out_of_bounds_offsets() {
+    let mut b = buffer(vec![0u8; 8]);
+    assert!(b.write(5, &[0u8; 4]).is_err(), "chunk runs past the end");
+    assert!(b.write(8, &[1]).is_err(), "offset at the end");
+    assert!(b.write(u32::MAX, &[1]).is_err());
+    assert!(b.write(8, &[]).is_ok(), "empty chunk at the end writes nothing");
+    assert_eq!(b.data, vec![0u8; 8], "rejected writes leave the buffer untouched");
+}
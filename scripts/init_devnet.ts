@@ -11,35 +11,35 @@ async function main() {
   console.log("Provider:", (provider.connection as any)._rpcEndpoint);
   console.log("Payer:", payer.publicKey.toBase58());
 
-  // Create a temporary mint and token account for the vault
-  console.log("Creating temp mint and token account...");
+  // Create a temporary mint for the first pool
+  console.log("Creating temp mint...");
   const mint = await createMint(provider.connection, payer, payer.publicKey, null, 0);
   const payerToken = await getOrCreateAssociatedTokenAccount(provider.connection, payer, mint, payer.publicKey);
   await mintTo(provider.connection, payer, mint, payerToken.address, payer.publicKey, 100);
 
-  // Derive PDAs and bumps
+  // Derive PDAs and bumps; pool PDAs are seeded by mint
   const [adminPda, adminBump] = await PublicKey.findProgramAddress([Buffer.from("admin")], program.programId);
-  const [treePda, treeBump] = await PublicKey.findProgramAddress([Buffer.from("tree_state")], program.programId);
-  const [managerPda, managerBump] = await PublicKey.findProgramAddress([Buffer.from("nullifier_manager")], program.programId);
-  const [vaultPda, vaultBump] = await PublicKey.findProgramAddress([Buffer.from("vault")], program.programId);
+  const [treePda] = await PublicKey.findProgramAddress([Buffer.from("tree_state"), mint.toBuffer()], program.programId);
+  const [managerPda] = await PublicKey.findProgramAddress([Buffer.from("nullifier_manager"), mint.toBuffer()], program.programId);
+  const [vaultPda] = await PublicKey.findProgramAddress([Buffer.from("vault"), mint.toBuffer()], program.programId);
+  const [poolConfigPda] = await PublicKey.findProgramAddress([Buffer.from("pool_config"), mint.toBuffer()], program.programId);
+  const vaultToken = await getOrCreateAssociatedTokenAccount(provider.connection, payer, mint, vaultPda, true);
 
   console.log("PDAs:", {
     admin: adminPda.toBase58(),
     tree: treePda.toBase58(),
     manager: managerPda.toBase58(),
     vault: vaultPda.toBase58(),
+    poolConfig: poolConfigPda.toBase58(),
   });
 
   // Call initialize on-chain
   console.log("Sending initialize transaction...");
 
   const tx = await program.methods
-    .initialize(payer.publicKey, payerToken.address, vaultBump, treeBump, new anchor.BN(4), adminBump)
+    .initialize(payer.publicKey, adminBump, new anchor.BN(48 * 60 * 60))
     .accounts({
       admin: adminPda,
-      tree_state: treePda,
-      nullifier_manager: managerPda,
-      vault: vaultPda,
       payer: payer.publicKey,
       system_program: anchor.web3.SystemProgram.programId,
       rent: anchor.web3.SYSVAR_RENT_PUBKEY,
@@ -47,6 +47,23 @@ async function main() {
     .rpc();
 
   console.log("Initialize tx signature:", tx);
+
+  console.log("Sending initialize_pool transaction...");
+  const poolTx = await program.methods
+    .initializePool(mint, 30)
+    .accounts({
+      admin: adminPda,
+      authority: payer.publicKey,
+      mint_account: mint,
+      vault: vaultPda,
+      vault_token_account: vaultToken.address,
+      tree_state: treePda,
+      nullifier_manager: managerPda,
+      pool_config: poolConfigPda,
+      system_program: anchor.web3.SystemProgram.programId,
+    })
+    .rpc();
+  console.log("Initialize pool tx signature:", poolTx);
   console.log("Done.");
 }
 
@@ -14,14 +14,14 @@ async function main() {
 
   console.log("Cluster:", (provider.connection as any)._rpcEndpoint);
 
-  // Check program exists by fetching account (tree_state PDA)
-  const [treePda] = await PublicKey.findProgramAddress([Buffer.from("tree_state")], program.programId);
-  const treeInfo = await provider.connection.getAccountInfo(treePda);
-  if (!treeInfo) {
-    throw new Error(`TreeState PDA ${treePda.toBase58()} not found; ensure program is deployed to devnet and Anchor.toml is updated`);
+  // Check program exists by fetching account (admin PDA; pools are per mint)
+  const [adminPda] = await PublicKey.findProgramAddress([Buffer.from("admin")], program.programId);
+  const adminInfo = await provider.connection.getAccountInfo(adminPda);
+  if (!adminInfo) {
+    throw new Error(`Admin PDA ${adminPda.toBase58()} not found; ensure program is deployed to devnet and Anchor.toml is updated`);
   }
 
-  console.log("Found TreeState account; running minimal deposit/withdraw flow (devnet). Note: requires deployer & relayer keys funded.)");
+  console.log("Found Admin account; running minimal deposit/withdraw flow (devnet). Note: requires deployer & relayer keys funded.)");
 
   // Create temporary test mint and accounts funded by provider wallet
   const payer = (provider.wallet as any).payer as Keypair;
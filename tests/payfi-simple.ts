@@ -16,54 +16,54 @@ describe("payfi-simple", () => {
     console.log("Program ID:", program.programId.toBase58());
 
     // Derive PDAs using the actual deployed program ID (from workspace)
-    const [adminPda] = await PublicKey.findProgramAddress([Buffer.from("admin")], program.programId);
-    const [treePda] = await PublicKey.findProgramAddress([Buffer.from("tree_state")], program.programId);
-    const [nullsManagerPda] = await PublicKey.findProgramAddress([Buffer.from("nullifier_manager")], program.programId);
-    const [vaultPda, vaultBump] = await PublicKey.findProgramAddress([Buffer.from("vault")], program.programId);
+    const [adminPda, adminBump] = await PublicKey.findProgramAddress([Buffer.from("admin")], program.programId);
+    // deny-list entry PDAs; they only exist for blocked addresses
+    const denyListPda = (addr: PublicKey) => PublicKey.findProgramAddressSync([
+      Buffer.from("deny_list"),
+      addr.toBuffer(),
+    ], program.programId)[0];
 
     // Check if initialized already
-    let adminInfo = await provider.connection.getAccountInfo(adminPda);
-    let vaultTokenAccountAddress: PublicKey;
-    let mint: any;
-    let payerTokenAccount: any;
-
+    const adminInfo = await provider.connection.getAccountInfo(adminPda);
     if (!adminInfo) {
       console.log("Initializing program for first time...");
-      // Create mint and token accounts
-      mint = await createMint(provider.connection, payerSigner, payerPubkey, null, 0);
-      payerTokenAccount = await getOrCreateAssociatedTokenAccount(provider.connection, payerSigner, mint, payerPubkey);
-      const vaultTokenAccount = await getOrCreateAssociatedTokenAccount(provider.connection, payerSigner, mint, vaultPda, true);
-      vaultTokenAccountAddress = vaultTokenAccount.address;
-
-      // Mint test tokens
-      await mintTo(provider.connection, payerSigner, mint, payerTokenAccount.address, payerPubkey, 1000);
-
-      // Initialize program
       await program.methods
-        .initialize(payerPubkey, vaultTokenAccountAddress, vaultBump, 0, new anchor.BN(1000), 0)
-        .accounts({
-          admin: adminPda,
-          treeState: treePda,
-          nullifierManager: nullsManagerPda,
-          vault: vaultPda,
-          payer: payerPubkey,
-          systemProgram: SystemProgram.programId,
-          rent: SYSVAR_RENT_PUBKEY,
-        })
+        .initialize(payerPubkey, adminBump, new anchor.BN(0))
+        .accounts({ admin: adminPda, payer: payerPubkey, systemProgram: SystemProgram.programId, rent: SYSVAR_RENT_PUBKEY })
         .rpc();
       console.log("Program initialized successfully");
     } else {
-      console.log("Program already initialized; reusing on-chain state");
-      const vaultState: any = await program.account.vault.fetch(vaultPda);
-      vaultTokenAccountAddress = vaultState.tokenAccount ?? vaultState.token_account;
+      console.log("Program already initialized; reusing on-chain admin");
+    }
 
-      const parsed = await provider.connection.getParsedAccountInfo(vaultTokenAccountAddress);
-      const parsedInfo: any = (parsed.value && (parsed.value as any).data && (parsed.value as any).data.parsed) ? (parsed.value as any).data.parsed.info : null;
-      if (!parsedInfo) throw new Error('Unable to parse vault token account');
-      mint = new PublicKey(parsedInfo.mint);
+    // Open a pool for a fresh mint; pool PDAs are seeded by mint
+    const mint = await createMint(provider.connection, payerSigner, payerPubkey, null, 0);
+    const [vaultPda] = await PublicKey.findProgramAddress([Buffer.from("vault"), mint.toBuffer()], program.programId);
+    const [treePda] = await PublicKey.findProgramAddress([Buffer.from("tree_state"), mint.toBuffer()], program.programId);
+    const [nullsManagerPda] = await PublicKey.findProgramAddress([Buffer.from("nullifier_manager"), mint.toBuffer()], program.programId);
+    const [poolConfigPda] = await PublicKey.findProgramAddress([Buffer.from("pool_config"), mint.toBuffer()], program.programId);
+    const payerTokenAccount = await getOrCreateAssociatedTokenAccount(provider.connection, payerSigner, mint, payerPubkey);
+    const vaultTokenAccount = await getOrCreateAssociatedTokenAccount(provider.connection, payerSigner, mint, vaultPda, true);
+    const vaultTokenAccountAddress = vaultTokenAccount.address;
 
-      payerTokenAccount = await getOrCreateAssociatedTokenAccount(provider.connection, payerSigner, mint, payerPubkey);
-    }
+    // Mint test tokens
+    await mintTo(provider.connection, payerSigner, mint, payerTokenAccount.address, payerPubkey, 1000);
+
+    await program.methods
+      .initializePool(mint, 30)
+      .accounts({
+        admin: adminPda,
+        authority: payerPubkey,
+        mintAccount: mint,
+        vault: vaultPda,
+        vaultTokenAccount: vaultTokenAccountAddress,
+        treeState: treePda,
+        nullifierManager: nullsManagerPda,
+        poolConfig: poolConfigPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    console.log("Pool initialized for mint", mint.toBase58());
 
     // Perform a deposit
     console.log("Performing deposit...");
@@ -71,11 +71,12 @@ describe("payfi-simple", () => {
     commitment[0] = 1;
 
     const depositTx = await program.methods
-      .deposit(new anchor.BN(100), Buffer.from(commitment), null)
+      .deposit(new anchor.BN(100), Buffer.from(commitment), null, null)
       .accounts({
         user: payerPubkey,
+        denyListEntry: denyListPda(payerPubkey),
         from: payerTokenAccount.address,
-        admin: adminPda,
+        poolConfig: poolConfigPda,
         vault: vaultPda,
         vaultTokenAccount: vaultTokenAccountAddress,
         treeState: treePda,
@@ -84,10 +85,10 @@ describe("payfi-simple", () => {
       .rpc();
     console.log("Deposit successful. Tx:", depositTx);
 
-    // Verify tree state updated
+    // Verify commitment was appended to the tree
     const treeState = await program.account.treeState.fetch(treePda);
-    if (Buffer.from(treeState.root).compare(Buffer.from(commitment)) !== 0) {
-      throw new Error("Tree root not updated after deposit");
+    if (treeState.nextIndex.toNumber() === 0) {
+      throw new Error("Commitment not appended to tree after deposit");
     }
     console.log("Tree root verified after deposit");
 
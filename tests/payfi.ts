@@ -1,14 +1,89 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
-import { PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY, Keypair } from "@solana/web3.js";
+import { PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY, SYSVAR_INSTRUCTIONS_PUBKEY, Keypair, Ed25519Program } from "@solana/web3.js";
 import { TOKEN_PROGRAM_ID, createMint, getOrCreateAssociatedTokenAccount, mintTo } from "@solana/spl-token";
 import { Payfi } from "../target/types/payfi";
+import { Verifier } from "../target/types/verifier";
 
 describe("payfi", () => {
   // Configure the client to use the local cluster.
   anchor.setProvider(anchor.AnchorProvider.env());
 
   const program = anchor.workspace.payfi as Program<Payfi>;
+  const verifierProgram = anchor.workspace.verifier as Program<Verifier>;
+
+  // Shared by the per-pool tests below; they rely on the first test having initialized the admin
+  const adminAddress = PublicKey.findProgramAddressSync([Buffer.from("admin")], program.programId)[0];
+  const denyListAddress = (addr: PublicKey) => PublicKey.findProgramAddressSync([Buffer.from("deny_list"), addr.toBuffer()], program.programId)[0];
+
+  // Sensitive configuration goes through the timelock queue; the tests use a 0s delay
+  const runChange = async (change: any, accounts: any = {}) => {
+    const payerPubkey = anchor.getProvider().wallet.publicKey;
+    const id = (await program.account.admin.fetch(adminAddress)).nextChangeId as anchor.BN;
+    const [pendingChange] = PublicKey.findProgramAddressSync([
+      Buffer.from("pending_change"),
+      id.toArrayLike(Buffer, "le", 8),
+    ], program.programId);
+    await program.methods
+      .queueChange(change)
+      .accounts({ admin: adminAddress, authority: payerPubkey, role: null, pendingChange, systemProgram: SystemProgram.programId })
+      .rpc();
+    await program.methods
+      .executeChange(id)
+      .accounts({ admin: adminAddress, executor: payerPubkey, pendingChange, queuedBy: payerPubkey, relayerRecord: null, verifierProgram: null, verificationKey: null, poolConfig: null, ...accounts })
+      .rpc();
+  };
+
+  // Create a mint, give the payer `amount` of it and open its pool with the stub verifier
+  const openPool = async (amount: number) => {
+    const provider = anchor.getProvider();
+    const payerPubkey = provider.wallet.publicKey;
+    const payerSigner = (provider.wallet as any).payer as Keypair;
+    const mint = await createMint(provider.connection, payerSigner, payerPubkey, null, 0);
+    const pda = (seed: string) => PublicKey.findProgramAddressSync([Buffer.from(seed), mint.toBuffer()], program.programId)[0];
+    const pool = { mint, vault: pda("vault"), treeState: pda("tree_state"), nullifierManager: pda("nullifier_manager"), poolConfig: pda("pool_config") };
+    const vaultTokenAccount = (await getOrCreateAssociatedTokenAccount(provider.connection, payerSigner, mint, pool.vault, true)).address;
+    const payerTokenAccount = (await getOrCreateAssociatedTokenAccount(provider.connection, payerSigner, mint, payerPubkey)).address;
+    await mintTo(provider.connection, payerSigner, mint, payerTokenAccount, payerPubkey, amount);
+    await program.methods
+      .initializePool(mint, 30)
+      .accounts({ admin: adminAddress, authority: payerPubkey, mintAccount: mint, vault: pool.vault, vaultTokenAccount, treeState: pool.treeState, nullifierManager: pool.nullifierManager, poolConfig: pool.poolConfig, systemProgram: SystemProgram.programId })
+      .rpc();
+    // new pools reject direct withdrawals until a verifier mode is queued
+    await runChange({ verifierMode: { mint, mode: 1, magic: Buffer.from([1]) } }, { poolConfig: pool.poolConfig });
+    return { ...pool, vaultTokenAccount, payerTokenAccount };
+  };
+  type Pool = Awaited<ReturnType<typeof openPool>>;
+
+  const depositInto = (pool: Pool, from: PublicKey, amount: number, commitmentTag: number) => {
+    const payerPubkey = anchor.getProvider().wallet.publicKey;
+    const commitment = Buffer.alloc(32);
+    commitment[0] = commitmentTag;
+    return program.methods
+      .deposit(new anchor.BN(amount), commitment, null, null)
+      .accounts({ user: payerPubkey, denyListEntry: denyListAddress(payerPubkey), from, poolConfig: pool.poolConfig, vault: pool.vault, vaultTokenAccount: pool.vaultTokenAccount, treeState: pool.treeState, tokenProgram: TOKEN_PROGRAM_ID })
+      .rpc();
+  };
+
+  // Direct withdrawal with the stub proof set up by `openPool`
+  const withdrawFrom = async (pool: Pool, nullifier: Buffer, recipientTokenAccount: PublicKey, recipient: PublicKey, amount: number) => {
+    const root = Buffer.from((await program.account.treeState.fetch(pool.treeState)).root);
+    const [nullifierRecord] = PublicKey.findProgramAddressSync([Buffer.from("nullifier"), pool.mint.toBuffer(), nullifier], program.programId);
+    return program.methods
+      .withdraw(Buffer.from([1]), nullifier, root, new anchor.BN(amount))
+      .accounts({ authority: anchor.getProvider().wallet.publicKey, admin: adminAddress, poolConfig: pool.poolConfig, vault: pool.vault, vaultTokenAccount: pool.vaultTokenAccount, recipientTokenAccount, denyListEntry: denyListAddress(recipient), treeState: pool.treeState, nullifierRecord, nullifierManager: pool.nullifierManager, verifierProgram: program.programId, verificationKey: null, proofBuffer: null, tokenProgram: TOKEN_PROGRAM_ID, systemProgram: SystemProgram.programId })
+      .rpc();
+  };
+
+  const expectFailure = async (action: Promise<unknown>, expected: string) => {
+    try {
+      await action;
+    } catch (err: any) {
+      if (!String(err).includes(expected)) throw err;
+      return;
+    }
+    throw new Error(`expected ${expected}, but the transaction succeeded`);
+  };
 
   it("initializes, deposits, and withdraws tokens", async () => {
     const provider = anchor.getProvider();
@@ -19,69 +94,54 @@ describe("payfi", () => {
     const [adminPda, adminBump] = await PublicKey.findProgramAddress([
       Buffer.from("admin")
     ], program.programId);
-    const [treePda, treeBump] = await PublicKey.findProgramAddress([
-      Buffer.from("tree_state")
-    ], program.programId);
-    // NullifierSet removed; use manager and chunk accounts instead
-    const [nullsManagerPda, nullsManagerBump] = await PublicKey.findProgramAddress([
-      Buffer.from("nullifier_manager")
-    ], program.programId);
-    const [vaultPda, vaultBump] = await PublicKey.findProgramAddress([
-      Buffer.from("vault")
-    ], program.programId);
-
-    // Idempotent initialization: if admin PDA exists, reuse on-chain vault token account and its mint
-    let adminInfo = await provider.connection.getAccountInfo(adminPda);
-    let mint: any;
-    let payerTokenAccount: any;
-    let vaultTokenAccountAddress: PublicKey;
-    const amount = 100;
-
+    // deny-list entry PDAs; they only exist for blocked addresses
+    const denyListPda = (addr: PublicKey) => PublicKey.findProgramAddressSync([
+      Buffer.from("deny_list"),
+      addr.toBuffer(),
+    ], program.programId)[0];
+
+    // Idempotent initialization: the admin account is created once, then every run opens a pool for a fresh mint
+    const adminInfo = await provider.connection.getAccountInfo(adminPda);
     if (!adminInfo) {
-      // Create a test mint and token accounts when initializing fresh
-      mint = await createMint(provider.connection, payerSigner, payerPubkey, null, 0);
-      payerTokenAccount = await getOrCreateAssociatedTokenAccount(provider.connection, payerSigner, mint, payerPubkey);
-      const vaultTokenAccount = await getOrCreateAssociatedTokenAccount(provider.connection, payerSigner, mint, vaultPda, true);
-      vaultTokenAccountAddress = vaultTokenAccount.address;
-
-      // Mint some tokens to payer
-      await mintTo(provider.connection, payerSigner, mint, payerTokenAccount.address, payerPubkey, amount);
-
       await program.methods
-        .initialize(payerPubkey, vaultTokenAccountAddress, vaultBump, treeBump, new anchor.BN(1000), adminBump)
-        .accounts({
-          admin: adminPda,
-          treeState: treePda,
-          nullifierManager: nullsManagerPda,
-          vault: vaultPda,
-          payer: payerPubkey,
-          systemProgram: SystemProgram.programId,
-          rent: SYSVAR_RENT_PUBKEY,
-        })
+        .initialize(payerPubkey, adminBump, new anchor.BN(0))
+        .accounts({ admin: adminPda, payer: payerPubkey, systemProgram: SystemProgram.programId, rent: SYSVAR_RENT_PUBKEY })
         .rpc();
     } else {
       console.log("Admin PDA exists; skipping initialize (idempotent)");
-      // Use on-chain vault token account and its mint instead of creating a new mint
-      const vaultState: any = await program.account.vault.fetch(vaultPda);
-      vaultTokenAccountAddress = vaultState.tokenAccount ?? vaultState.token_account;
-      console.log("Using existing vault token account:", vaultTokenAccountAddress.toString());
-
-      // read the token account to find the mint
-      const parsed = await provider.connection.getParsedAccountInfo(vaultTokenAccountAddress);
-      const parsedInfo: any = (parsed.value && (parsed.value as any).data && (parsed.value as any).data.parsed) ? (parsed.value as any).data.parsed.info : null;
-      if (!parsedInfo) throw new Error('Unable to parse vault token account info to determine mint');
-      mint = new PublicKey(parsedInfo.mint);
-
-      // get or create payer token account for that mint
-      payerTokenAccount = await getOrCreateAssociatedTokenAccount(provider.connection, payerSigner, mint, payerPubkey);
-
-      // Ensure payer has tokens for testing on existing mint
-      const payerBal = await provider.connection.getTokenAccountBalance(payerTokenAccount.address).catch(()=>null);
-      if (!payerBal || parseInt(payerBal.value.amount) === 0) {
-        console.log('Payer has zero balance on existing mint; you may need to fund payer token account manually for devnet tests');
-      }
     }
 
+    const amount = 100;
+    const mint = await createMint(provider.connection, payerSigner, payerPubkey, null, 0);
+    // pool PDAs are seeded by mint, so each token gets its own vault, tree and nullifier set
+    const poolPda = (seed: string) => PublicKey.findProgramAddressSync([Buffer.from(seed), mint.toBuffer()], program.programId)[0];
+    const vaultPda = poolPda("vault");
+    const treePda = poolPda("tree_state");
+    const nullsManagerPda = poolPda("nullifier_manager");
+    const poolConfigPda = poolPda("pool_config");
+
+    const payerTokenAccount = await getOrCreateAssociatedTokenAccount(provider.connection, payerSigner, mint, payerPubkey);
+    const vaultTokenAccount = await getOrCreateAssociatedTokenAccount(provider.connection, payerSigner, mint, vaultPda, true);
+    const vaultTokenAccountAddress = vaultTokenAccount.address;
+
+    // Mint some tokens to payer
+    await mintTo(provider.connection, payerSigner, mint, payerTokenAccount.address, payerPubkey, amount);
+
+    await program.methods
+      .initializePool(mint, 30)
+      .accounts({
+        admin: adminPda,
+        authority: payerPubkey,
+        mintAccount: mint,
+        vault: vaultPda,
+        vaultTokenAccount: vaultTokenAccountAddress,
+        treeState: treePda,
+        nullifierManager: nullsManagerPda,
+        poolConfig: poolConfigPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
     // Prepare a fake commitment and encrypted note
     const commitment = new Uint8Array(32);
     commitment[0] = 1; // simple non-zero commitment for test
@@ -96,11 +156,12 @@ describe("payfi", () => {
     console.log("Vault mint:", vaultMint, "Vault owner:", vaultOwner, "expected vaultPDA:", vaultPda.toBase58(), "Payer mint:", payerMint);
 
     await program.methods
-      .deposit(new anchor.BN(amount), Buffer.from(commitment), null)
+      .deposit(new anchor.BN(amount), Buffer.from(commitment), null, null)
       .accounts({
         user: payerPubkey,
+        denyListEntry: denyListPda(payerPubkey),
         from: payerTokenAccount.address,
-        admin: adminPda,
+        poolConfig: poolConfigPda,
         vault: vaultPda,
         vaultTokenAccount: vaultTokenAccountAddress,
         treeState: treePda,
@@ -108,11 +169,12 @@ describe("payfi", () => {
       })
       .rpc();
 
-    // Verify tree root updated to our commitment placeholder
+    // Verify commitment was appended as a leaf and the root advanced
     const treeState = await program.account.treeState.fetch(treePda);
-    if (Buffer.from(treeState.root).compare(Buffer.from(commitment)) !== 0) {
-      throw new Error("Tree root not updated")
+    if (treeState.nextIndex.toNumber() === 0) {
+      throw new Error("Commitment not appended to tree")
     }
+    const root = Buffer.from(treeState.root);
 
     // Prepare withdraw: recipient token account
     const recipient = Keypair.generate();
@@ -125,40 +187,33 @@ describe("payfi", () => {
     const nullifier = new Uint8Array(32);
     nullifier[0] = 7;
 
-    // compute chunk index from nullifier prefix
-    const prefix = Number(Buffer.from(nullifier.slice(0, 8)).readBigUInt64LE(0));
-    const chunkIndex = Math.floor(prefix / 256);
-    const chunkIndexBuf = Buffer.alloc(8);
-    chunkIndexBuf.writeBigUInt64LE(BigInt(chunkIndex), 0);
-    const [chunkPda] = await PublicKey.findProgramAddress([
-      Buffer.from("nullifier_chunk"),
-      chunkIndexBuf,
+    const [nullifierPda] = await PublicKey.findProgramAddress([
+      Buffer.from("nullifier"),
+      mint.toBuffer(),
+      Buffer.from(nullifier),
     ], program.programId);
 
-    // init chunk (idempotent: skip if already exists)
-    let existing = await provider.connection.getAccountInfo(chunkPda);
-    if (!existing) {
-      await program.methods
-        .initNullifierChunk(new anchor.BN(chunkIndex))
-        .accounts({ chunk: chunkPda, manager: nullsManagerPda, payer: payerPubkey, systemProgram: SystemProgram.programId })
-        .rpc();
-    }
-
     // Attempt withdraw with invalid proof (should fail)
     try {
       await program.methods
-        .withdraw(Buffer.from([]), Buffer.from(nullifier), Buffer.from(commitment), new anchor.BN(amount))
+        .withdraw(Buffer.from([]), Buffer.from(nullifier), root, new anchor.BN(amount))
         .accounts({
           authority: payerPubkey,
           admin: adminPda,
+          poolConfig: poolConfigPda,
           vault: vaultPda,
           vaultTokenAccount: vaultTokenAccountAddress,
           recipientTokenAccount: recipientTokenAccount.address,
+          denyListEntry: denyListPda(recipient.publicKey),
           treeState: treePda,
-          nullifierChunk: chunkPda,
+          nullifierRecord: nullifierPda,
+          nullifierManager: nullsManagerPda,
           verifierProgram: program.programId,
+          verificationKey: null,
+          proofBuffer: null,
           verifier_program: program.programId,
           tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
         })
         .rpc();
       throw new Error("Withdraw unexpectedly succeeded with invalid proof");
@@ -167,25 +222,28 @@ describe("payfi", () => {
     }
 
     // Set verifier mode to stub and magic to [1]
-    await program.methods
-      .setVerifierMode(1, Buffer.from([1]))
-      .accounts({ admin: adminPda, authority: payerPubkey, verifierProgram: program.programId, verifier_program: program.programId })
-      .rpc();
+    await runChange({ verifierMode: { mint, mode: 1, magic: Buffer.from([1]) } }, { poolConfig: poolConfigPda });
 
     // Withdraw with correct stub proof
     await program.methods
-      .withdraw(Buffer.from([1]), Buffer.from(nullifier), Buffer.from(commitment), new anchor.BN(amount))
+      .withdraw(Buffer.from([1]), Buffer.from(nullifier), root, new anchor.BN(amount))
       .accounts({
         authority: payerPubkey,
         admin: adminPda,
+        poolConfig: poolConfigPda,
         vault: vaultPda,
         vaultTokenAccount: vaultTokenAccountAddress,
         recipientTokenAccount: recipientTokenAccount.address,
+        denyListEntry: denyListPda(recipient.publicKey),
         treeState: treePda,
-        nullifierChunk: chunkPda,
+        nullifierRecord: nullifierPda,
+        nullifierManager: nullsManagerPda,
         verifierProgram: program.programId,
+        verificationKey: null,
+        proofBuffer: null,
         verifier_program: program.programId,
         tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
       })
       .rpc();
 
@@ -195,6 +253,73 @@ describe("payfi", () => {
       throw new Error("Recipient did not receive tokens")
     }
 
+    // --- Buffered proof: stage the proof in a verifier ProofBuffer and withdraw with an empty inline proof ---
+    await mintTo(provider.connection, payerSigner, mint, payerTokenAccount.address, payerPubkey, amount);
+    const commitmentBuffered = new Uint8Array(32);
+    commitmentBuffered[0] = 3;
+    await program.methods
+      .deposit(new anchor.BN(amount), Buffer.from(commitmentBuffered), null, null)
+      .accounts({
+        user: payerPubkey,
+        denyListEntry: denyListPda(payerPubkey),
+        from: payerTokenAccount.address,
+        poolConfig: poolConfigPda,
+        vault: vaultPda,
+        vaultTokenAccount: vaultTokenAccountAddress,
+        treeState: treePda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    const rootBuffered = Buffer.from((await program.account.treeState.fetch(treePda)).root);
+
+    const bufferId = new anchor.BN(Date.now());
+    const [proofBufferPda] = PublicKey.findProgramAddressSync([
+      Buffer.from("proof_buffer"),
+      payerPubkey.toBuffer(),
+      bufferId.toArrayLike(Buffer, "le", 8),
+    ], verifierProgram.programId);
+    await verifierProgram.methods
+      .initProofBuffer(bufferId, 1)
+      .accounts({ proofBuffer: proofBufferPda, authority: payerPubkey, systemProgram: SystemProgram.programId })
+      .rpc();
+    await verifierProgram.methods
+      .writeProofChunk(0, Buffer.from([1]))
+      .accounts({ proofBuffer: proofBufferPda, authority: payerPubkey })
+      .rpc();
+
+    const nullifierBuffered = new Uint8Array(32);
+    nullifierBuffered[0] = 8;
+    const [nullifierBufferedPda] = PublicKey.findProgramAddressSync([
+      Buffer.from("nullifier"),
+      mint.toBuffer(),
+      Buffer.from(nullifierBuffered),
+    ], program.programId);
+    const before = parseInt((await provider.connection.getTokenAccountBalance(recipientTokenAccount.address)).value.amount);
+    await program.methods
+      .withdraw(Buffer.from([]), Buffer.from(nullifierBuffered), rootBuffered, new anchor.BN(amount))
+      .accounts({
+        authority: payerPubkey,
+        admin: adminPda,
+        poolConfig: poolConfigPda,
+        vault: vaultPda,
+        vaultTokenAccount: vaultTokenAccountAddress,
+        recipientTokenAccount: recipientTokenAccount.address,
+        denyListEntry: denyListPda(recipient.publicKey),
+        treeState: treePda,
+        nullifierRecord: nullifierBufferedPda,
+        nullifierManager: nullsManagerPda,
+        verifierProgram: program.programId,
+        verificationKey: null,
+        proofBuffer: proofBufferPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    resp = await provider.connection.getTokenAccountBalance(recipientTokenAccount.address);
+    if (parseInt(resp.value.amount) !== before + amount) {
+      throw new Error("Withdraw did not read the proof from the buffer")
+    }
+
     // --- Relayer demo: deposit again and withdraw via relayer ---
     // Mint more tokens to payer and deposit
     await mintTo(provider.connection, payerSigner, mint, payerTokenAccount.address, payerPubkey, amount);
@@ -203,17 +328,19 @@ describe("payfi", () => {
     commitment2[0] = 2;
 
     await program.methods
-      .deposit(new anchor.BN(amount), Buffer.from(commitment2), null)
+      .deposit(new anchor.BN(amount), Buffer.from(commitment2), null, null)
       .accounts({
         user: payerPubkey,
+        denyListEntry: denyListPda(payerPubkey),
         from: payerTokenAccount.address,
-        admin: adminPda,
+        poolConfig: poolConfigPda,
         vault: vaultPda,
         vaultTokenAccount: vaultTokenAccountAddress,
         treeState: treePda,
         tokenProgram: TOKEN_PROGRAM_ID,
       })
       .rpc();
+    const root2 = Buffer.from((await program.account.treeState.fetch(treePda)).root);
 
     // Prepare a second recipient
     const recipient2 = Keypair.generate();
@@ -223,69 +350,83 @@ describe("payfi", () => {
 
     const nullifier2 = new Uint8Array(32);
     nullifier2[0] = 9;
-    // ensure this targets a different chunk than the first (set second byte so prefix >= 256)
-    nullifier2[1] = 1;
-
-    const prefix2 = Number(Buffer.from(nullifier2.slice(0, 8)).readBigUInt64LE(0));
-    const chunkIndex2 = Math.floor(prefix2 / 256);
-    const chunkIndexBuf2 = Buffer.alloc(8);
-    chunkIndexBuf2.writeBigUInt64LE(BigInt(chunkIndex2), 0);
-    const [chunkPda2] = await PublicKey.findProgramAddress([
-      Buffer.from("nullifier_chunk"),
-      chunkIndexBuf2,
-    ], program.programId);
 
-    // init chunk2 (idempotent)
-    let existing2 = await provider.connection.getAccountInfo(chunkPda2);
-    if (!existing2) {
-      await program.methods
-        .initNullifierChunk(new anchor.BN(chunkIndex2))
-        .accounts({ chunk: chunkPda2, manager: nullsManagerPda, payer: payerPubkey, systemProgram: SystemProgram.programId })
-        .rpc();
-    }
+    const [nullifierPda2] = await PublicKey.findProgramAddress([
+      Buffer.from("nullifier"),
+      mint.toBuffer(),
+      Buffer.from(nullifier2),
+    ], program.programId);
 
     // Add relayer and execute relayer withdraw
     const relayer = Keypair.generate();
     await provider.connection.requestAirdrop(relayer.publicKey, 1e9);
 
-    await program.methods
-      .addRelayer(relayer.publicKey)
-      .accounts({ admin: adminPda, authority: payerPubkey })
-      .rpc();
-
-    // For now we do not require on-chain relayer_state for withdraw flow (rate-limiting will be added later)
-    // Relayer is registered below and will sign the withdraw transaction.
-
-    // initialize relayer state (PDA) and set a small rate limit for test
+    // initialize relayer state (PDA) and bond escrow with a small rate limit for test
     const relayerWindow = 60; // seconds
     const relayerLimit = 1; // allow only 1 withdraw per window
 
     const relayerSeed = Buffer.from("relayer_state");
-    const [relayerStatePda, relayerStateBump] = await PublicKey.findProgramAddress([
+    // relayer records and bond escrows are per pool, keyed by (mint, relayer)
+    const [relayerRecordPda, relayerRecordBump] = await PublicKey.findProgramAddress([
       relayerSeed,
+      mint.toBuffer(),
+      relayer.publicKey.toBuffer(),
+    ], program.programId);
+    const [bondEscrowPda] = await PublicKey.findProgramAddress([
+      Buffer.from("relayer_bond"),
+      mint.toBuffer(),
       relayer.publicKey.toBuffer(),
     ], program.programId);
 
     await program.methods
-      .initRelayerState(relayer.publicKey, new anchor.BN(relayerLimit), new anchor.BN(relayerWindow))
-      .accounts({ relayerState: relayerStatePda, payer: payerPubkey, systemProgram: SystemProgram.programId })
+      .initRelayerRecord(relayer.publicKey, new anchor.BN(relayerLimit), new anchor.BN(relayerWindow), new anchor.BN(1_000_000))
+      .accounts({ admin: adminPda, authority: payerPubkey, role: null, relayerRecord: relayerRecordPda, vault: vaultPda, vaultTokenAccount: vaultTokenAccountAddress, mint: new PublicKey(mint), bondEscrow: bondEscrowPda, payer: payerPubkey, tokenProgram: TOKEN_PROGRAM_ID, systemProgram: SystemProgram.programId, rent: SYSVAR_RENT_PUBKEY })
       .rpc();
 
-    // Build attestation message and signature (nullifier || root || recipient_pubkey || amount || expiry)
+    // The relayer must post a bond before it can be registered
+    const relayerFeeAccount = await getOrCreateAssociatedTokenAccount(provider.connection, payerSigner, new PublicKey(mint), relayer.publicKey);
+    await mintTo(provider.connection, payerSigner, new PublicKey(mint), relayerFeeAccount.address, payerSigner, 1_000);
+    await program.methods
+      .bondRelayer(new anchor.BN(1_000))
+      .accounts({ relayer: relayer.publicKey, relayerRecord: relayerRecordPda, relayerTokenAccount: relayerFeeAccount.address, bondEscrow: bondEscrowPda, tokenProgram: TOKEN_PROGRAM_ID })
+      .signers([relayer])
+      .rpc();
+
+    await runChange({ addRelayer: { mint, relayer: relayer.publicKey } }, { relayerRecord: relayerRecordPda, poolConfig: poolConfigPda });
+
+    // Relayer is registered above and will sign the withdraw transaction; withdraw_by_relayer
+    // enforces the relayer record limits below.
+
+    // Build attestation message and signature: Borsh-encoded Attestation
+    // (version || program_id || pool || mint || nullifier || root || recipient || amount || fee || expiry)
+    const attestationMessage = (nullifierBytes: Uint8Array, expiry: Buffer) => Buffer.concat([
+      Buffer.from([1]),
+      program.programId.toBuffer(),
+      vaultPda.toBuffer(),
+      new PublicKey(mint).toBuffer(),
+      Buffer.from(nullifierBytes),
+      root2,
+      recipientTokenAccount2.address.toBuffer(),
+      amountBuf,
+      feeBuf,
+      expiry,
+    ]);
     const nacl = require('tweetnacl');
     const attestationExpiry = Math.floor(Date.now() / 1000) + 60; // expiry in unix seconds
     const amountBuf = Buffer.alloc(8);
     amountBuf.writeBigUInt64LE(BigInt(amount), 0);
+    const feeBuf = Buffer.alloc(8); // fee = 0: max_fee_bps is unset
     const expiryBuf = Buffer.alloc(8);
     expiryBuf.writeBigUInt64LE(BigInt(attestationExpiry), 0);
 
-    const message = Buffer.concat([Buffer.from(nullifier2), Buffer.from(commitment2), recipient2.publicKey.toBuffer(), amountBuf, expiryBuf]);
+    const message = attestationMessage(nullifier2, expiryBuf);
     const sig = nacl.sign.detached(new Uint8Array(message), relayer.secretKey);
 
     // Relayer performs withdraw (signer)
     await program.methods
-      .withdrawByRelayer(Buffer.from(nullifier2), Buffer.from(commitment2), new anchor.BN(amount), Buffer.from(sig), relayer.publicKey, new anchor.BN(attestationExpiry))
-      .accounts({ relayer: relayer.publicKey, admin: adminPda, vault: vaultPda, vaultTokenAccount: vaultTokenAccountAddress, recipientTokenAccount: recipientTokenAccount2.address, treeState: treePda, nullifierChunk: chunkPda2, relayerState: relayerStatePda, tokenProgram: TOKEN_PROGRAM_ID })
+      .withdrawByRelayer(Buffer.from(nullifier2), root2, new anchor.BN(amount), new anchor.BN(0), relayer.publicKey, new anchor.BN(attestationExpiry))
+      .accounts({ relayer: relayer.publicKey, poolConfig: poolConfigPda, vault: vaultPda, vaultTokenAccount: vaultTokenAccountAddress, recipientTokenAccount: recipientTokenAccount2.address, denyListEntry: denyListPda(recipient2.publicKey), relayerFeeAccount: relayerFeeAccount.address, treeState: treePda, nullifierRecord: nullifierPda2, nullifierManager: nullsManagerPda, relayerRecord: relayerRecordPda, instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY, tokenProgram: TOKEN_PROGRAM_ID, systemProgram: SystemProgram.programId })
+      .preInstructions([Ed25519Program.createInstructionWithPublicKey({ publicKey: relayer.publicKey.toBytes(), message: message, signature: sig })])
       .signers([relayer])
       .rpc();
 
@@ -297,33 +438,23 @@ describe("payfi", () => {
     // Attempt withdraw with expired attestation (should fail)
     const nullifier3 = new Uint8Array(32);
     nullifier3[0] = 11;
-    const prefix3 = Number(Buffer.from(nullifier3.slice(0, 8)).readBigUInt64LE(0));
-    const chunkIndex3 = Math.floor(prefix3 / 256);
-    const chunkIndexBuf3 = Buffer.alloc(8);
-    chunkIndexBuf3.writeBigUInt64LE(BigInt(chunkIndex3), 0);
-    const [chunkPda3] = await PublicKey.findProgramAddress([
-      Buffer.from("nullifier_chunk"),
-      chunkIndexBuf3,
+    const [nullifierPda3] = await PublicKey.findProgramAddress([
+      Buffer.from("nullifier"),
+      mint.toBuffer(),
+      Buffer.from(nullifier3),
     ], program.programId);
 
-    let existing3 = await provider.connection.getAccountInfo(chunkPda3);
-    if (!existing3) {
-      await program.methods
-        .initNullifierChunk(new anchor.BN(chunkIndex3))
-        .accounts({ chunk: chunkPda3, manager: nullsManagerPda, payer: payerPubkey, systemProgram: SystemProgram.programId })
-        .rpc();
-    }
-
     const expiredExpiry = Math.floor(Date.now() / 1000) - 10;
     const expiryBufExpired = Buffer.alloc(8);
     expiryBufExpired.writeBigUInt64LE(BigInt(expiredExpiry), 0);
-    const messageExpired = Buffer.concat([Buffer.from(nullifier3), Buffer.from(commitment2), recipient2.publicKey.toBuffer(), amountBuf, expiryBufExpired]);
+    const messageExpired = attestationMessage(nullifier3, expiryBufExpired);
     const sigExpired = nacl.sign.detached(new Uint8Array(messageExpired), relayer.secretKey);
 
     try {
       await program.methods
-        .withdrawByRelayer(Buffer.from(nullifier3), Buffer.from(commitment2), new anchor.BN(amount), Buffer.from(sigExpired), relayer.publicKey, new anchor.BN(expiredExpiry))
-        .accounts({ relayer: relayer.publicKey, admin: adminPda, vault: vaultPda, vaultTokenAccount: vaultTokenAccountAddress, recipientTokenAccount: recipientTokenAccount2.address, treeState: treePda, nullifierChunk: chunkPda3, relayerState: relayerStatePda, tokenProgram: TOKEN_PROGRAM_ID })
+        .withdrawByRelayer(Buffer.from(nullifier3), root2, new anchor.BN(amount), new anchor.BN(0), relayer.publicKey, new anchor.BN(expiredExpiry))
+        .accounts({ relayer: relayer.publicKey, poolConfig: poolConfigPda, vault: vaultPda, vaultTokenAccount: vaultTokenAccountAddress, recipientTokenAccount: recipientTokenAccount2.address, denyListEntry: denyListPda(recipient2.publicKey), relayerFeeAccount: relayerFeeAccount.address, treeState: treePda, nullifierRecord: nullifierPda3, nullifierManager: nullsManagerPda, relayerRecord: relayerRecordPda, instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY, tokenProgram: TOKEN_PROGRAM_ID, systemProgram: SystemProgram.programId })
+        .preInstructions([Ed25519Program.createInstructionWithPublicKey({ publicKey: relayer.publicKey.toBytes(), message: messageExpired, signature: sigExpired })])
         .signers([relayer])
         .rpc();
       throw new Error("Expired attestation unexpectedly succeeded");
@@ -334,30 +465,20 @@ describe("payfi", () => {
     // Attempt second valid withdraw to trigger rate limit (should fail)
     const nullifier4 = new Uint8Array(32);
     nullifier4[0] = 13;
-    const prefix4 = Number(Buffer.from(nullifier4.slice(0, 8)).readBigUInt64LE(0));
-    const chunkIndex4 = Math.floor(prefix4 / 256);
-    const chunkIndexBuf4 = Buffer.alloc(8);
-    chunkIndexBuf4.writeBigUInt64LE(BigInt(chunkIndex4), 0);
-    const [chunkPda4] = await PublicKey.findProgramAddress([
-      Buffer.from("nullifier_chunk"),
-      chunkIndexBuf4,
+    const [nullifierPda4] = await PublicKey.findProgramAddress([
+      Buffer.from("nullifier"),
+      mint.toBuffer(),
+      Buffer.from(nullifier4),
     ], program.programId);
 
-    let existing4 = await provider.connection.getAccountInfo(chunkPda4);
-    if (!existing4) {
-      await program.methods
-        .initNullifierChunk(new anchor.BN(chunkIndex4))
-        .accounts({ chunk: chunkPda4, manager: nullsManagerPda, payer: payerPubkey, systemProgram: SystemProgram.programId })
-        .rpc();
-    }
-
-    const message2 = Buffer.concat([Buffer.from(nullifier4), Buffer.from(commitment2), recipient2.publicKey.toBuffer(), amountBuf, expiryBuf]);
+    const message2 = attestationMessage(nullifier4, expiryBuf);
     const sig2 = nacl.sign.detached(new Uint8Array(message2), relayer.secretKey);
 
     try {
       await program.methods
-        .withdrawByRelayer(Buffer.from(nullifier4), Buffer.from(commitment2), new anchor.BN(amount), Buffer.from(sig2), relayer.publicKey, new anchor.BN(attestationExpiry))
-        .accounts({ relayer: relayer.publicKey, admin: adminPda, vault: vaultPda, vaultTokenAccount: vaultTokenAccountAddress, recipientTokenAccount: recipientTokenAccount2.address, treeState: treePda, nullifierChunk: chunkPda4, relayerState: relayerStatePda, tokenProgram: TOKEN_PROGRAM_ID })
+        .withdrawByRelayer(Buffer.from(nullifier4), root2, new anchor.BN(amount), new anchor.BN(0), relayer.publicKey, new anchor.BN(attestationExpiry))
+        .accounts({ relayer: relayer.publicKey, poolConfig: poolConfigPda, vault: vaultPda, vaultTokenAccount: vaultTokenAccountAddress, recipientTokenAccount: recipientTokenAccount2.address, denyListEntry: denyListPda(recipient2.publicKey), relayerFeeAccount: relayerFeeAccount.address, treeState: treePda, nullifierRecord: nullifierPda4, nullifierManager: nullsManagerPda, relayerRecord: relayerRecordPda, instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY, tokenProgram: TOKEN_PROGRAM_ID, systemProgram: SystemProgram.programId })
+        .preInstructions([Ed25519Program.createInstructionWithPublicKey({ publicKey: relayer.publicKey.toBytes(), message: message2, signature: sig2 })])
         .signers([relayer])
         .rpc();
       throw new Error("Second relayer withdraw unexpectedly succeeded (should be rate limited)");
@@ -365,4 +486,76 @@ describe("payfi", () => {
       // expected
     }
   });
+
+  it("opens two pools side by side", async () => {
+    const provider = anchor.getProvider();
+    const usdc = await openPool(100);
+    const usdt = await openPool(100);
+
+    await depositInto(usdc, usdc.payerTokenAccount, 40, 21);
+    await depositInto(usdt, usdt.payerTokenAccount, 70, 22);
+
+    // each pool keeps its own tree, config and vault balance
+    for (const [pool, balance] of [[usdc, 40], [usdt, 70]] as [Pool, number][]) {
+      const tree = await program.account.treeState.fetch(pool.treeState);
+      if (tree.nextIndex.toNumber() !== 1) throw new Error("deposit landed in the wrong pool's tree");
+      const config = await program.account.poolConfig.fetch(pool.poolConfig);
+      if (!config.mint.equals(pool.mint)) throw new Error("pool config belongs to another mint");
+      const vault = await provider.connection.getTokenAccountBalance(pool.vaultTokenAccount);
+      if (Number(vault.value.amount) !== balance) throw new Error("vault balance mixed across pools");
+    }
+  });
+
+  it("rejects token accounts of another mint", async () => {
+    const provider = anchor.getProvider();
+    const payerSigner = (provider.wallet as any).payer as Keypair;
+    const pool = await openPool(100);
+    const other = await openPool(100);
+
+    await expectFailure(depositInto(pool, other.payerTokenAccount, 50, 23), "InvalidMint");
+
+    // a note deposited in `pool` cannot be paid out in `other`'s token
+    await depositInto(pool, pool.payerTokenAccount, 50, 24);
+    const recipient = Keypair.generate();
+    const wrongMintRecipient = await getOrCreateAssociatedTokenAccount(provider.connection, payerSigner, other.mint, recipient.publicKey);
+    const nullifier = Buffer.alloc(32);
+    nullifier[0] = 31;
+    await expectFailure(withdrawFrom(pool, nullifier, wrongMintRecipient.address, recipient.publicKey, 50), "InvalidMint");
+  });
+
+  it("rejects a second spend of the same nullifier", async () => {
+    const provider = anchor.getProvider();
+    const payerSigner = (provider.wallet as any).payer as Keypair;
+    const pool = await openPool(100);
+    await depositInto(pool, pool.payerTokenAccount, 50, 41);
+    await depositInto(pool, pool.payerTokenAccount, 50, 42);
+
+    const recipient = Keypair.generate();
+    const recipientTokenAccount = await getOrCreateAssociatedTokenAccount(provider.connection, payerSigner, pool.mint, recipient.publicKey);
+    const nullifier = Buffer.alloc(32);
+    nullifier[0] = 51;
+    await withdrawFrom(pool, nullifier, recipientTokenAccount.address, recipient.publicKey, 50);
+    // the nullifier record is already stamped as spent
+    await expectFailure(withdrawFrom(pool, nullifier, recipientTokenAccount.address, recipient.publicKey, 50), "NullifierAlreadyUsed");
+  });
+
+  it("spends nullifiers that share a prefix independently", async () => {
+    const provider = anchor.getProvider();
+    const payerSigner = (provider.wallet as any).payer as Keypair;
+    const pool = await openPool(100);
+    await depositInto(pool, pool.payerTokenAccount, 50, 43);
+    await depositInto(pool, pool.payerTokenAccount, 50, 44);
+
+    const recipient = Keypair.generate();
+    const recipientTokenAccount = await getOrCreateAssociatedTokenAccount(provider.connection, payerSigner, pool.mint, recipient.publicKey);
+    // identical except for the last byte; records are keyed by the full 32 bytes
+    const first = Buffer.alloc(32, 0xab);
+    const second = Buffer.from(first);
+    second[31] = 0xac;
+    await withdrawFrom(pool, first, recipientTokenAccount.address, recipient.publicKey, 50);
+    await withdrawFrom(pool, second, recipientTokenAccount.address, recipient.publicKey, 50);
+
+    const manager = await program.account.nullifierManager.fetch(pool.nullifierManager);
+    if (manager.count.toNumber() !== 2) throw new Error("both nullifiers should be recorded");
+  });
 });